@@ -1,9 +1,14 @@
 use {
     crate::{
+        col::{TableCol, ALL_COLS},
+        col_expr::ParseExprError,
         cols::Cols,
+        dedup::Dedup,
         filter::Filter,
+        format::Format,
+        lustre_proc::LustreProc,
         units::Units,
-        sorting::Sorting,
+        sorting::{ParseSortingError, Sorting},
     },
     clap::{Parser, ValueEnum},
     termimad::crossterm::tty::IsTty,
@@ -49,35 +54,101 @@ pub struct Args {
     #[arg(short = 'i', long)]
     pub inodes: bool,
 
-    /// columns, eg `-c +inodes` or `-c id+dev+default`
-    #[arg(short, long, default_value = "fs+used+use+free+size+fsname", value_name = "columns")]
+    /// columns, eg `-c +inodes` (one column), `-c +inodes_all`
+    /// (all inode columns), or `-c id+dev+default`
+    #[arg(id = "cols", short = 'c', long = "cols", value_name = "columns")]
+    pub cols_def: Option<String>,
+
+    /// the columns, computed from the command line, the view,
+    /// and the configuration
+    #[arg(skip)]
     pub cols: Cols,
 
+    /// whether the columns were given with `-c`, the serialized
+    /// outputs being then limited to them
+    #[arg(skip)]
+    pub cols_given: bool,
+
+    /// named view, eg `--view full-disks` (also `@full-disks`)
+    #[arg(long, value_name = "name")]
+    pub view: Option<String>,
+
     /// filter, eg `-f '(size<35G | remote=false) & type=xfs'`
     #[arg(short, long, value_name = "expr")]
-    pub filter: Option<Filter>,
+    pub filter: Option<String>,
 
-    /// sort, eg `inodes`, `type-desc`, or `size-asc`
-    #[arg(short, long, default_value = "size", value_name = "sort")]
-    pub sort: Sorting,
+    /// sort, eg `inodes`, `type-desc`, or `type,size-asc` (default: `size`)
+    #[arg(short, long, value_name = "sort")]
+    pub sort: Option<String>,
 
     /// units: `SI` (SI norm), `binary` (1024 based), or `bytes` (raw number)
     #[arg(short, long, default_value = "SI", value_name = "unit")]
     pub units: Units,
 
-    /// output as JSON
-    #[arg(short, long)]
+    /// merge the mounts of a same device (`device`), of a same
+    /// filesystem source (`fs`), or don't merge them (`none`)
+    #[arg(long, default_value = "device", value_name = "mode")]
+    pub dedup: Dedup,
+
+    /// mount point representing its filesystem when mounts are merged
+    #[arg(long, value_name = "path")]
+    pub prefer_mount: Vec<PathBuf>,
+
+    /// add a row with the totals of the displayed filesystems
+    #[arg(long, conflicts_with_all = ["jobs", "server", "changelogs"])]
+    pub total: bool,
+
+    /// output format: `table`, `json`, `csv`, `yaml`, `toml`, `ndjson`, `influx`, or `graphite`
+    #[arg(long, value_name = "format")]
+    pub format: Option<Format>,
+
+    /// output as JSON (same as `--format json`)
+    #[arg(short, long, conflicts_with_all = ["format", "csv"])]
     pub json: bool,
 
-    /// output as CSV
+    /// print the JSON Schema of the JSON output
     #[arg(long)]
+    pub json_schema: bool,
+
+    /// output as CSV (same as `--format csv`)
+    #[arg(long, conflicts_with = "format")]
     pub csv: bool,
 
+    /// list the filesystems again every this many seconds
+    #[arg(long, value_name = "seconds", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: Option<u64>,
+
+    /// with `--interval`, stop after this many listings
+    #[arg(long, value_name = "count", requires = "interval")]
+    pub count: Option<u64>,
+
     /// CSV separator
     #[arg(long, default_value = ",", value_name = "sep")]
     pub csv_separator: char,
 
+    /// list the activity of jobs on the local Lustre targets (needs
+    /// jobstats), always with all the job columns
+    #[arg(long, conflicts_with_all = ["cols", "server", "changelogs"])]
+    pub jobs: bool,
+
+    /// list the Lustre targets (MDT and OST) served by this node
+    #[arg(long, conflicts_with = "changelogs")]
+    pub server: bool,
+
+    /// list the changelog users of the local MDTs, and how late they are
+    #[arg(long)]
+    pub changelogs: bool,
+
+    /// ignore the configuration files
+    #[arg(long)]
+    pub no_config: bool,
+
+    /// root of the Lustre procfs tree
+    #[arg(long, hide = true, value_name = "dir")]
+    pub proc_root: Option<PathBuf>,
+
     /// if provided, only the device holding this path will be shown
+    /// (`@name` selects a view)
     pub path: Option<PathBuf>,
 }
 
@@ -104,7 +175,85 @@ impl TriBool {
 }
 
 impl Args {
+    /// The output format, given with `--format`, `--json`, or `--csv`
+    pub fn format(&self) -> Format {
+        match self.format {
+            Some(format) => format,
+            None if self.json => Format::Json,
+            None if self.csv => Format::Csv,
+            None => Format::Table,
+        }
+    }
+    /// The columns the serialized outputs are limited to, None when
+    /// they weren't chosen with `-c` or when they're all chosen
+    pub fn serialized_cols(&self) -> Option<&Cols> {
+        Some(&self.cols).filter(|cols| self.cols_given && cols.cols().len() < ALL_COLS.len())
+    }
     pub fn color(&self) -> bool {
         self.color.unwrap_or_else(|| std::io::stdout().is_tty())
     }
-}
\ No newline at end of file
+    /// The sorting of the rows of the view, parsed for its columns
+    pub fn sorting<C: TableCol>(&self) -> Result<Sorting<C>, ParseSortingError> {
+        match &self.sort {
+            Some(s) => s.parse(),
+            None => Ok(Sorting::default()),
+        }
+    }
+    /// The filter of the rows of the view, parsed for its columns
+    pub fn row_filter<C: TableCol>(&self) -> Result<Filter<C>, ParseExprError> {
+        match &self.filter {
+            Some(s) => s.parse(),
+            None => Ok(Filter::default()),
+        }
+    }
+    /// The name of the view given with `--view` or as `@name`, a
+    /// `@name` argument being removed from the path
+    pub fn take_view_name(&mut self) -> Option<String> {
+        let name = self
+            .path
+            .as_ref()
+            .and_then(|path| path.to_str())
+            .and_then(|path| path.strip_prefix('@'))
+            .map(str::to_string);
+        if name.is_some() {
+            self.path = None;
+            return name;
+        }
+        self.view.clone()
+    }
+    pub fn lustre_proc(&self) -> LustreProc {
+        match &self.proc_root {
+            Some(root) => LustreProc::new(root),
+            None => LustreProc::default(),
+        }
+    }
+}
+#[test]
+fn test_jobs_reject_cols() {
+    use clap::CommandFactory;
+    let parse = |cli: &[&str]| Args::command().try_get_matches_from(cli.iter().copied());
+    assert!(parse(&["dysk", "--jobs", "-s", "write"]).is_ok());
+    assert!(parse(&["dysk", "--jobs", "-c", "target"]).is_err());
+}
+
+#[test]
+fn test_views_conflict() {
+    use clap::CommandFactory;
+    let parse = |cli: &[&str]| Args::command().try_get_matches_from(cli.iter().copied());
+    assert!(parse(&["dysk", "--server"]).is_ok());
+    assert!(parse(&["dysk", "--total", "-c", "+inodes"]).is_ok());
+    assert!(parse(&["dysk", "--jobs", "--server"]).is_err());
+    assert!(parse(&["dysk", "--jobs", "--changelogs"]).is_err());
+    assert!(parse(&["dysk", "--server", "--changelogs"]).is_err());
+    assert!(parse(&["dysk", "--total", "--jobs"]).is_err());
+    assert!(parse(&["dysk", "--total", "--server"]).is_err());
+    assert!(parse(&["dysk", "--total", "--changelogs"]).is_err());
+}
+
+#[test]
+fn test_interval_isnt_zero() {
+    use clap::CommandFactory;
+    let parse = |cli: &[&str]| Args::command().try_get_matches_from(cli.iter().copied());
+    assert!(parse(&["dysk", "--interval", "5"]).is_ok());
+    assert!(parse(&["dysk", "--interval", "0"]).is_err());
+}
@@ -0,0 +1,151 @@
+use {
+    crate::lustre_proc::LustreProc,
+    std::io,
+};
+
+/// A changelog consumer registered on a MDT
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangelogUser {
+    /// the MDT, eg "lustre-MDT0000"
+    pub mdt: String,
+    /// the id of the user, eg "cl1" or "cl2-robinhood"
+    pub id: String,
+    /// index of the last record cleared by this user
+    pub last_cleared: u64,
+    /// index of the last record produced by the MDT
+    pub current_index: u64,
+    /// seconds since the user last cleared records, when
+    /// reported by the Lustre version
+    pub idle: Option<u64>,
+}
+
+impl ChangelogUser {
+    /// number of records the MDT keeps because of this user
+    pub fn lag(&self) -> u64 {
+        self.current_index.saturating_sub(self.last_cleared)
+    }
+}
+
+/// Read the changelog users of all the local MDTs
+pub fn read_changelog_users(proc: &LustreProc) -> io::Result<Vec<ChangelogUser>> {
+    let mut users = Vec::new();
+    for mdt in proc.targets("mdd")? {
+        if let Some(content) = proc.read_param("mdd", &mdt, "changelog_users")? {
+            users.append(&mut parse_changelog_users(&mdt, &content));
+        }
+    }
+    Ok(users)
+}
+
+/// Parse the content of a changelog_users file, which looks like
+///
+/// ```text
+/// current_index: 1287
+/// ID                            index (idle) mask
+/// cl1                           1215 (3600)
+/// cl2-robinhood                 1287 (5)
+/// ```
+///
+/// (older versions have neither idle times nor masks and
+/// say "current index")
+pub fn parse_changelog_users(mdt: &str, content: &str) -> Vec<ChangelogUser> {
+    let mut current_index = 0;
+    let mut users = Vec::new();
+    for line in content.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            if key.trim() == "current_index" || key.trim() == "current index" {
+                current_index = value.trim().parse().unwrap_or(0);
+            }
+            continue;
+        }
+        let mut tokens = line.split_whitespace();
+        let (Some(id), Some(index)) = (tokens.next(), tokens.next()) else {
+            continue;
+        };
+        let Ok(last_cleared) = index.parse() else {
+            continue; // the header
+        };
+        let idle = tokens
+            .next()
+            .and_then(|t| t.strip_prefix('('))
+            .and_then(|t| t.strip_suffix(')'))
+            .and_then(|t| t.trim_end_matches('s').parse().ok());
+        users.push(ChangelogUser {
+            mdt: mdt.to_string(),
+            id: id.to_string(),
+            last_cleared,
+            current_index: 0,
+            idle,
+        });
+    }
+    for user in &mut users {
+        user.current_index = current_index;
+    }
+    users
+}
+
+/// Format a duration in seconds with its two most significant units,
+/// eg "3d 4h" or "12m 5s"
+pub fn format_duration(secs: u64) -> String {
+    let units = [("d", 24 * 3600), ("h", 3600), ("m", 60), ("s", 1)];
+    let mut parts = Vec::new();
+    let mut rest = secs;
+    for (name, len) in units {
+        if rest >= len || (parts.is_empty() && len == 1) {
+            parts.push(format!("{}{}", rest / len, name));
+            rest %= len;
+        } else if !parts.is_empty() {
+            break;
+        }
+        if parts.len() == 2 {
+            break;
+        }
+    }
+    parts.join(" ")
+}
+
+#[test]
+fn test_parse_changelog_users() {
+    let content = "current_index: 1287\n\
+        ID                            index (idle) mask\n\
+        cl1                           1215 (3600)\n\
+        cl2-robinhood                 1287 (5s) MARK,CREAT\n";
+    let users = parse_changelog_users("lustre-MDT0000", content);
+    assert_eq!(
+        users,
+        vec![
+            ChangelogUser {
+                mdt: "lustre-MDT0000".to_string(),
+                id: "cl1".to_string(),
+                last_cleared: 1215,
+                current_index: 1287,
+                idle: Some(3600),
+            },
+            ChangelogUser {
+                mdt: "lustre-MDT0000".to_string(),
+                id: "cl2-robinhood".to_string(),
+                last_cleared: 1287,
+                current_index: 1287,
+                idle: Some(5),
+            },
+        ],
+    );
+    assert_eq!(users[0].lag(), 72);
+    // old format
+    let users = parse_changelog_users("lustre-MDT0001", "current index: 40\nID    index\ncl1   12\n");
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].lag(), 28);
+    assert_eq!(users[0].idle, None);
+    assert!(parse_changelog_users("lustre-MDT0000", "current_index: 0\nID    index (idle) mask\n").is_empty());
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(0), "0s");
+    assert_eq!(format_duration(5), "5s");
+    assert_eq!(format_duration(60), "1m");
+    assert_eq!(format_duration(725), "12m 5s");
+    assert_eq!(format_duration(3600), "1h");
+    assert_eq!(format_duration(3 * 24 * 3600 + 4 * 3600 + 59), "3d 4h");
+    assert_eq!(format_duration(24 * 3600 + 30), "1d");
+}
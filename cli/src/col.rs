@@ -1,8 +1,13 @@
 use {
-    crate::order::Order,
+    crate::{
+        col_value::ColValue,
+        lustre_target::LustreTargetName,
+        mount_row::MountRow,
+        num_expr::NumKind,
+        order::Order,
+    },
     lfs_core::Mount,
     std::{
-        cmp::Ordering,
         fmt,
         str::FromStr,
     },
@@ -22,6 +27,28 @@ pub fn extract_fsname(mount: &Mount) -> String {
     }
 }
 
+/// A column of a view, usable in sort and filter expressions
+/// on the rows of this view
+pub trait TableCol: Copy + PartialEq + fmt::Debug + FromStr<Err = ParseColError> {
+    /// the type of the rows of the view
+    type Row;
+    fn default_sort_col() -> Self;
+    fn default_sort_order(self) -> Order;
+    /// whether the values of the column are text, which
+    /// can be matched with a regex or a glob
+    fn is_text(self) -> bool;
+    /// the kind of the values of the column, None when they
+    /// can't be used in arithmetic expressions
+    fn num_kind(self) -> Option<NumKind>;
+    /// the value of the column for a row, from which sorting
+    /// and filtering are computed
+    fn value(self, row: &Self::Row) -> ColValue;
+    /// the value of the column for a row, when it's numeric
+    fn number(self, row: &Self::Row) -> Option<f64> {
+        self.value(row).number()
+    }
+}
+
 macro_rules! col_enum {
     (@just_variant $variant:ident $discarded:ident) => {
         Col::$variant
@@ -114,8 +141,9 @@ col_enum!(
     InodesUsePercent "inodes_use_percent" "iuse_percent": "inodes%" "inodes%",
     InodesFree "inodes_free" "ifree": "free inodes" "free inodes",
     InodesCount "inodes_total" "inodes_count" "itotal": "inodes total" "inodes total",
-    MountPoint "mount" "mount_point" "mp": "mount point" "mount point" default,
-    FsName "fsname" "fs_name": "filesystem name" "fsname",
+    MountPoint "mount" "mount_point" "mp": "mount point" "mount point",
+    Mounts "mounts": "mount points" "mount points",
+    FsName "fsname" "fs_name": "filesystem name" "fsname" default,
     Uuid "uuid": "UUID" "UUID",
     PartUuid "partuuid" "part_uuid": "PARTUUID" "PARTUUID",
     StripeCount "stripe_count" "stripes": "stripe count" "stripe count",
@@ -124,14 +152,29 @@ col_enum!(
     PoolName "pool_name" "pool": "pool name" "pool name",
     ComponentType "component_type" "comp_type": "component type" "component type",
     ComponentIndex "component_index" "comp_idx": "component index" "component index",
+    Target "target": "target" "target",
     MirrorCount "mirror_count" "mirrors": "mirror count" "mirror count",
+    HsmState "hsm_state" "hsm": "HSM state" "HSM state",
+    HsmArchiveId "hsm_archive_id" "archive_id": "archive id" "archive id",
+    HsmCoordinator "hsm_coordinator" "cdt": "HSM coordinator" "HSM coordinator",
+    HsmPending "hsm_pending": "HSM pending" "HSM pending",
+    HsmActive "hsm_active": "HSM active" "HSM active",
 );
 
+/// The named sets of columns coming with dysk, usable like
+/// columns in `--cols`, eg `dysk -c +lustre-ids`
+pub static BUILTIN_COL_SETS: &[(&str, &[Col])] = &[
+    ("ids", &[Col::Id, Col::Dev, Col::Uuid, Col::PartUuid]),
+    ("inodes_all", &[Col::InodesUsed, Col::InodesUse, Col::InodesFree, Col::InodesCount]),
+    ("lustre", &[Col::StripeCount, Col::StripeSize, Col::PoolName, Col::MirrorCount, Col::LustreVersion]),
+];
+
 impl Col {
     pub fn header_align(self) -> Alignment {
         match self {
             Self::Label => Alignment::Left,
             Self::MountPoint => Alignment::Left,
+            Self::Mounts => Alignment::Left,
             Self::FsName => Alignment::Left,
             _ => Alignment::Center,
         }
@@ -157,6 +200,7 @@ impl Col {
             Self::InodesFree => Alignment::Center,
             Self::InodesCount => Alignment::Center,
             Self::MountPoint => Alignment::Left,
+            Self::Mounts => Alignment::Left,
             Self::FsName => Alignment::Left,
             Self::Uuid => Alignment::Left,
             Self::PartUuid => Alignment::Left,
@@ -166,7 +210,13 @@ impl Col {
             Self::PoolName => Alignment::Left,
             Self::ComponentType => Alignment::Center,
             Self::ComponentIndex => Alignment::Center,
+            Self::Target => Alignment::Left,
             Self::MirrorCount => Alignment::Center,
+            Self::HsmState => Alignment::Left,
+            Self::HsmArchiveId => Alignment::Center,
+            Self::HsmCoordinator => Alignment::Center,
+            Self::HsmPending => Alignment::Right,
+            Self::HsmActive => Alignment::Right,
         }
     }
     pub fn description(self) -> &'static str {
@@ -190,6 +240,7 @@ impl Col {
             Self::InodesFree => "number of free inodes",
             Self::InodesCount => "total count of inodes",
             Self::MountPoint => "mount point",
+            Self::Mounts => "mount points of the filesystem, when several mounts are merged (see --dedup)",
             Self::FsName => "filesystem name",
             Self::Uuid => "filesystem UUID",
             Self::PartUuid => "partition UUID",
@@ -199,147 +250,85 @@ impl Col {
             Self::PoolName => "OST pool name for workload isolation",
             Self::ComponentType => "type of Lustre component (MDT/OST/CLIENT)",
             Self::ComponentIndex => "index number of the component",
+            Self::Target => "name of the Lustre target (eg lustre-OST0001), none for a client",
             Self::MirrorCount => "number of file mirrors for data replication",
+            Self::HsmState => "HSM flags of the mount point or given path (exists, archived, released, dirty, lost)",
+            Self::HsmArchiveId => "id of the HSM archive of the mount point or given path",
+            Self::HsmCoordinator => "state of the HSM coordinator (only known on a MDS)",
+            Self::HsmPending => "number of HSM actions waiting for a copytool (only known on a MDS)",
+            Self::HsmActive => "number of HSM actions being done by a copytool (only known on a MDS)",
         }
     }
-    pub fn comparator(self) -> impl for<'a, 'b> FnMut(&'a Mount, &'b Mount) -> Ordering {
+    /// The value of the column for a row
+    pub fn value(self, mount: &MountRow) -> ColValue {
+        let lustre_info = || mount.lustre.as_ref();
+        let stats = mount.stats();
+        let inodes = mount.inodes();
         match self {
-            Self::Id => |a: &Mount, b: &Mount| a.info.id.cmp(&b.info.id),
-            Self::Dev => |a: &Mount, b: &Mount| a.info.dev.cmp(&b.info.dev),
-            Self::Filesystem =>  |a: &Mount, b: &Mount| a.info.fs.cmp(&b.info.fs),
-            Self::Label =>  |a: &Mount, b: &Mount| match (&a.fs_label, &b.fs_label) {
-                (Some(a), Some(b)) => a.cmp(b),
-                (Some(_), None) => Ordering::Less,
-                (None, Some(_)) => Ordering::Greater,
-                (None, None) => Ordering::Equal,
-            },
-            Self::Type =>  |a: &Mount, b: &Mount| a.info.fs_type.cmp(&b.info.fs_type),
-            Self::Remote =>  |a: &Mount, b: &Mount| a.info.is_remote().cmp(&b.info.is_remote()),
-            Self::Disk =>  |a: &Mount, b: &Mount| match (&a.disk, &b.disk) {
-                (Some(a), Some(b)) => a.disk_type().to_lowercase().cmp(&b.disk_type().to_lowercase()),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::Used =>  |a: &Mount, b: &Mount| match (&a.stats(), &b.stats()) {
-                (Some(a), Some(b)) => a.used().cmp(&b.used()),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::Use | Self::UsePercent =>  |a: &Mount, b: &Mount| match (&a.stats(), &b.stats()) {
-                // SAFETY: use_share() doesn't return NaN
-                (Some(a), Some(b)) => a.use_share().partial_cmp(&b.use_share()).unwrap(),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::Free =>  |a: &Mount, b: &Mount| match (&a.stats(), &b.stats()) {
-                (Some(a), Some(b)) => a.available().cmp(&b.available()),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::FreePercent =>  |a: &Mount, b: &Mount| match (&a.stats(), &b.stats()) {
-                (Some(a), Some(b)) => b.use_share().partial_cmp(&a.use_share()).unwrap(),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::Size =>  |a: &Mount, b: &Mount| match (&a.stats(), &b.stats()) {
-                (Some(a), Some(b)) => a.size().cmp(&b.size()),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::InodesUsed =>  |a: &Mount, b: &Mount| match (&a.inodes(), &b.inodes()) {
-                (Some(a), Some(b)) => a.used().cmp(&b.used()),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::InodesUsePercent | Self::InodesUse  =>  |a: &Mount, b: &Mount| match (&a.inodes(), &b.inodes()) {
-                // SAFETY: use_share() doesn't return NaN
-                (Some(a), Some(b)) => a.use_share().partial_cmp(&b.use_share()).unwrap(),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::InodesFree =>  |a: &Mount, b: &Mount| match (&a.inodes(), &b.inodes()) {
-                (Some(a), Some(b)) => a.favail.cmp(&b.favail),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::InodesCount =>  |a: &Mount, b: &Mount| match (&a.inodes(), &b.inodes()) {
-                (Some(a), Some(b)) => a.files.cmp(&b.files),
-                (Some(_), None) => Ordering::Greater,
-                (None, Some(_)) => Ordering::Less,
-                (None, None) => Ordering::Equal,
-            },
-            Self::MountPoint =>  |a: &Mount, b: &Mount| a.info.mount_point.cmp(&b.info.mount_point),
-            Self::FsName => |a: &Mount, b: &Mount| extract_fsname(a).cmp(&extract_fsname(b)),
-            Self::Uuid => |a: &Mount, b: &Mount| match (&a.uuid, &b.uuid) {
-                (Some(a), Some(b)) => a.cmp(b),
-                (Some(_), None) => Ordering::Less,
-                (None, Some(_)) => Ordering::Greater,
-                (None, None) => Ordering::Equal,
-            },
-            Self::PartUuid => |a: &Mount, b: &Mount| match (&a.part_uuid, &b.part_uuid) {
-                (Some(a), Some(b)) => a.cmp(b),
-                (Some(_), None) => Ordering::Less,
-                (None, Some(_)) => Ordering::Greater,
-                (None, None) => Ordering::Equal,
-            },
-            Self::StripeCount => |a: &Mount, b: &Mount| {
-                let a_point = a.info.mount_point.to_string_lossy();
-                let b_point = b.info.mount_point.to_string_lossy();
-                let a_info = crate::get_lustre_info(&a_point).and_then(|i| i.stripe_count).unwrap_or(0);
-                let b_info = crate::get_lustre_info(&b_point).and_then(|i| i.stripe_count).unwrap_or(0);
-                a_info.cmp(&b_info)
-            },
-            Self::StripeSize => |a: &Mount, b: &Mount| {
-                let a_point = a.info.mount_point.to_string_lossy();
-                let b_point = b.info.mount_point.to_string_lossy();
-                let a_info = crate::get_lustre_info(&a_point).and_then(|i| i.stripe_size).unwrap_or(0);
-                let b_info = crate::get_lustre_info(&b_point).and_then(|i| i.stripe_size).unwrap_or(0);
-                a_info.cmp(&b_info)
-            },
-            Self::LustreVersion => |a: &Mount, b: &Mount| {
-                let a_point = a.info.mount_point.to_string_lossy();
-                let b_point = b.info.mount_point.to_string_lossy();
-                let a_info = crate::get_lustre_info(&a_point).and_then(|i| i.lustre_version).unwrap_or_else(|| "".to_string());
-                let b_info = crate::get_lustre_info(&b_point).and_then(|i| i.lustre_version).unwrap_or_else(|| "".to_string());
-                a_info.cmp(&b_info)
-            },
-            Self::PoolName => |a: &Mount, b: &Mount| {
-                let a_point = a.info.mount_point.to_string_lossy();
-                let b_point = b.info.mount_point.to_string_lossy();
-                let a_info = crate::get_lustre_info(&a_point).and_then(|i| i.pool_name).unwrap_or_else(|| "".to_string());
-                let b_info = crate::get_lustre_info(&b_point).and_then(|i| i.pool_name).unwrap_or_else(|| "".to_string());
-                a_info.cmp(&b_info)
-            },
-            Self::ComponentType => |a: &Mount, b: &Mount| {
-                let a_point = a.info.mount_point.to_string_lossy();
-                let b_point = b.info.mount_point.to_string_lossy();
-                let a_info = crate::get_lustre_info(&a_point).and_then(|i| i.component_type).unwrap_or_else(|| "".to_string());
-                let b_info = crate::get_lustre_info(&b_point).and_then(|i| i.component_type).unwrap_or_else(|| "".to_string());
-                a_info.cmp(&b_info)
-            },
-            Self::ComponentIndex => |a: &Mount, b: &Mount| {
-                let a_point = a.info.mount_point.to_string_lossy();
-                let b_point = b.info.mount_point.to_string_lossy();
-                let a_info = crate::get_lustre_info(&a_point).and_then(|i| i.component_index).unwrap_or(u32::MAX);
-                let b_info = crate::get_lustre_info(&b_point).and_then(|i| i.component_index).unwrap_or(u32::MAX);
-                a_info.cmp(&b_info)
-            },
-            Self::MirrorCount => |a: &Mount, b: &Mount| {
-                let a_point = a.info.mount_point.to_string_lossy();
-                let b_point = b.info.mount_point.to_string_lossy();
-                let a_info = crate::get_lustre_info(&a_point).and_then(|i| i.mirror_count).unwrap_or(0);
-                let b_info = crate::get_lustre_info(&b_point).and_then(|i| i.mirror_count).unwrap_or(0);
-                a_info.cmp(&b_info)
-            },
+            Self::Id => ColValue::Int(mount.info.id.into()),
+            Self::Dev => ColValue::Device(mount.info.dev),
+            Self::Filesystem => ColValue::Text(mount.info.fs.clone()),
+            Self::Label => ColValue::text(mount.fs_label.as_ref()),
+            Self::Type => ColValue::Text(mount.info.fs_type.clone()),
+            Self::Remote => ColValue::Bool(mount.info.is_remote()),
+            Self::Disk => ColValue::text(mount.disk.as_ref().map(|d| d.disk_type())),
+            Self::Used => ColValue::size(stats.map(|s| s.used())),
+            Self::Use | Self::UsePercent => ColValue::float(stats.map(|s| s.use_share())),
+            Self::Free => ColValue::size(stats.map(|s| s.available())),
+            Self::FreePercent => ColValue::float(stats.map(|s| 1.0 - s.use_share())),
+            Self::Size => ColValue::size(stats.map(|s| s.size())),
+            Self::InodesUsed => ColValue::int(inodes.map(|i| i.used())),
+            Self::InodesUse | Self::InodesUsePercent => ColValue::float(inodes.map(|i| i.use_share())),
+            Self::InodesFree => ColValue::int(inodes.map(|i| i.favail)),
+            Self::InodesCount => ColValue::int(inodes.map(|i| i.files)),
+            Self::MountPoint => ColValue::Text(mount.info.mount_point.to_string_lossy().to_string()),
+            Self::Mounts => ColValue::List(
+                mount.mount_points
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+            ),
+            Self::FsName => ColValue::Text(extract_fsname(mount)),
+            Self::Uuid => ColValue::text(mount.uuid.as_ref()),
+            Self::PartUuid => ColValue::text(mount.part_uuid.as_ref()),
+            Self::StripeCount => ColValue::int(lustre_info().and_then(|i| i.stripe_count)),
+            Self::StripeSize => ColValue::size(lustre_info().and_then(|i| i.stripe_size)),
+            Self::LustreVersion => lustre_info().and_then(|i| i.lustre_version.clone()).map_or(ColValue::Missing, ColValue::Version),
+            // Lustre gives an empty name for the default pool
+            Self::PoolName => ColValue::text(
+                lustre_info()
+                    .and_then(|i| i.pool_name.as_deref())
+                    .map(|pool| if pool.is_empty() { "default" } else { pool }),
+            ),
+            Self::ComponentType => ColValue::text(lustre_info().map(|i| i.component_type())),
+            Self::ComponentIndex => ColValue::int(lustre_info().and_then(|i| i.component_index())),
+            Self::Target => Some(mount)
+                .filter(|m| m.info.fs_type == "lustre")
+                .and_then(|m| m.info.fs.parse::<LustreTargetName>().ok())
+                .map_or(ColValue::Missing, ColValue::Target),
+            Self::MirrorCount => ColValue::int(lustre_info().and_then(|i| i.mirror_count)),
+            Self::HsmState => ColValue::text(lustre_info().and_then(|i| i.hsm.as_ref()).map(|h| h.summary())),
+            // the archive id is meaningless when the file isn't archived
+            Self::HsmArchiveId => ColValue::int(lustre_info().and_then(|i| i.hsm.as_ref()).filter(|h| h.archived()).map(|h| h.archive_id)),
+            Self::HsmCoordinator => ColValue::text(lustre_info().and_then(|i| i.hsm_coordinator.as_ref()).map(|c| &c.state)),
+            Self::HsmPending => ColValue::int(lustre_info().and_then(|i| i.hsm_coordinator.as_ref()).map(|c| c.pending_actions as u64)),
+            Self::HsmActive => ColValue::int(lustre_info().and_then(|i| i.hsm_coordinator.as_ref()).map(|c| c.active_actions as u64)),
+        }
+    }
+    /// The value shown in the column, which, for the byte columns,
+    /// is about inodes in inodes mode (`-i`)
+    pub fn displayed_value(self, mount: &MountRow, inodes_mode: bool) -> ColValue {
+        if !inodes_mode {
+            return self.value(mount);
+        }
+        match self {
+            Self::Used => Self::InodesUsed.value(mount),
+            Self::Use => Self::InodesUse.value(mount),
+            Self::UsePercent => Self::InodesUsePercent.value(mount),
+            Self::Free => Self::InodesFree.value(mount),
+            Self::FreePercent => ColValue::float(mount.inodes().map(|i| 1.0 - i.use_share())),
+            Self::Size => Self::InodesCount.value(mount),
+            _ => self.value(mount),
         }
     }
     pub fn default_sort_order(self) -> Order {
@@ -363,6 +352,7 @@ impl Col {
             Self::InodesFree => Order::Asc,
             Self::InodesCount => Order::Asc,
             Self::MountPoint => Order::Asc,
+            Self::Mounts => Order::Asc,
             Self::FsName => Order::Asc,
             Self::Uuid => Order::Asc,
             Self::PartUuid => Order::Asc,
@@ -372,7 +362,13 @@ impl Col {
             Self::PoolName => Order::Asc,
             Self::ComponentType => Order::Asc,
             Self::ComponentIndex => Order::Asc,
+            Self::Target => Order::Asc,
             Self::MirrorCount => Order::Desc,
+            Self::HsmState => Order::Asc,
+            Self::HsmArchiveId => Order::Asc,
+            Self::HsmCoordinator => Order::Asc,
+            Self::HsmPending => Order::Desc,
+            Self::HsmActive => Order::Desc,
         }
     }
     pub fn default_sort_col() -> Self {
@@ -380,6 +376,61 @@ impl Col {
     }
 }
 
+impl TableCol for Col {
+    type Row = MountRow;
+    fn default_sort_col() -> Self {
+        Col::default_sort_col()
+    }
+    fn default_sort_order(self) -> Order {
+        Col::default_sort_order(self)
+    }
+    fn is_text(self) -> bool {
+        matches!(
+            self,
+            Self::Filesystem
+                | Self::Label
+                | Self::Type
+                | Self::Disk
+                | Self::MountPoint
+                | Self::Mounts
+                | Self::FsName
+                | Self::Uuid
+                | Self::PartUuid
+                | Self::LustreVersion
+                | Self::PoolName
+                | Self::ComponentType
+                | Self::Target
+                | Self::HsmState
+                | Self::HsmCoordinator
+        )
+    }
+    fn num_kind(self) -> Option<NumKind> {
+        match self {
+            Self::Use
+            | Self::UsePercent
+            | Self::FreePercent
+            | Self::InodesUse
+            | Self::InodesUsePercent => Some(NumKind::Share),
+            Self::Used
+            | Self::Free
+            | Self::Size
+            | Self::InodesUsed
+            | Self::InodesFree
+            | Self::InodesCount
+            | Self::StripeCount
+            | Self::StripeSize
+            | Self::ComponentIndex
+            | Self::MirrorCount
+            | Self::HsmArchiveId
+            | Self::HsmPending
+            | Self::HsmActive => Some(NumKind::Amount),
+            _ => None,
+        }
+    }
+    fn value(self, mount: &MountRow) -> ColValue {
+        Col::value(self, mount)
+    }
+}
 
 #[derive(Debug)]
 pub struct ParseColError {
@@ -400,4 +451,22 @@ impl fmt::Display for ParseColError {
         )
     }
 }
-impl std::error::Error for ParseColError {}
\ No newline at end of file
+impl std::error::Error for ParseColError {}
+#[test]
+fn test_values_are_the_same_in_all_outputs() {
+    use crate::{filter::Filter, test_util, units::Units, LustreInfo};
+    let mut row = test_util::mount(30, "/lustre").fs_type("lustre").row();
+    let mut info = LustreInfo::new();
+    info.pool_name = Some(String::new());
+    row.lustre = Some(info);
+    let pool = Col::PoolName.value(&row);
+    assert_eq!(pool, ColValue::Text("default".to_string()));
+    assert_eq!(pool.display(Units::Si).as_deref(), Some("default"));
+    assert_eq!(pool.to_json(), serde_json::json!("default"));
+    let filter: Filter = "pool=default & pool is not null".parse().unwrap();
+    assert!(filter.eval(&row).unwrap());
+    let local = test_util::mount(31, "/home").row();
+    let remote = Col::Remote.value(&local);
+    assert_eq!(remote.display(Units::Si).as_deref(), Some("no"));
+    assert!("remote=no".parse::<Filter>().unwrap().eval(&local).unwrap());
+}
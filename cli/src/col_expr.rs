@@ -1,9 +1,16 @@
 use {
     crate::{
         col::*,
+        col_value::ColValue,
+        filter_lexer::{self, ExprToken, Spanned, Token},
+        lustre_target::LustreTargetName,
+        natural::version_cmp,
+        num_expr::{self, NumComparison, NumExpr},
     },
     lfs_core::*,
+    regex::Regex,
     std::{
+        cmp::Ordering,
         fmt,
         str::FromStr,
     },
@@ -18,8 +25,38 @@ pub enum ColOperator {
     NotEqual,
     GreaterOrEqual,
     Greater,
+    Matches,
+    NotMatches,
+    Glob,
+    NotGlob,
+    In,
+    IsNull,
+    IsNotNull,
 }
 
+/// The operators, longest first so that a prefix of an
+/// operator isn't taken for the operator
+pub(crate) static OPERATORS: &[(&str, ColOperator)] = &[
+    ("!*=", ColOperator::NotGlob),
+    ("<=", ColOperator::LowerOrEqual),
+    ("==", ColOperator::Equal),
+    ("<>", ColOperator::NotEqual),
+    (">=", ColOperator::GreaterOrEqual),
+    ("!~", ColOperator::NotMatches),
+    ("*=", ColOperator::Glob),
+    ("<", ColOperator::Lower),
+    ("=", ColOperator::Like),
+    (">", ColOperator::Greater),
+    ("~", ColOperator::Matches),
+];
+
+/// The operators written as keywords
+pub(crate) static KEYWORD_OPERATORS: &[(&str, ColOperator)] = &[
+    ("in", ColOperator::In),
+    ("is", ColOperator::IsNull),
+    ("is not", ColOperator::IsNotNull),
+];
+
 impl ColOperator {
     pub fn eval<T: PartialOrd+PartialEq>(self, a: T, b: T) -> bool {
         match self {
@@ -29,13 +66,21 @@ impl ColOperator {
             Self::NotEqual => a != b,
             Self::GreaterOrEqual => a >= b,
             Self::Greater => a > b,
+            // pattern operators are only accepted on text columns
+            // (see ColExpr::from_str) and evaluated by ColExpr
+            Self::Matches | Self::NotMatches | Self::Glob | Self::NotGlob => false,
+            // a `in` expression is evaluated by its members, and
+            // null tests by ColExpr
+            Self::In | Self::IsNull | Self::IsNotNull => false,
         }
     }
-    pub fn eval_option<T: PartialOrd+PartialEq>(self, a: Option<T>, b: T) -> bool {
-        match a {
-            Some(a) => self.eval(a, b),
-            None => false,
-        }
+    /// Whether the operator is a regex or glob match
+    pub fn is_pattern(self) -> bool {
+        matches!(self, Self::Matches | Self::NotMatches | Self::Glob | Self::NotGlob)
+    }
+    /// Whether the operator is `is null` or `is not null`
+    pub fn is_null_test(self) -> bool {
+        matches!(self, Self::IsNull | Self::IsNotNull)
     }
     pub fn eval_str(self, a: &str, b: &str) -> bool {
         match self {
@@ -43,210 +88,232 @@ impl ColOperator {
             _ => self.eval(a, b),
         }
     }
-    pub fn eval_option_str(self, a: Option<&str>, b: &str) -> bool {
-        match (a, self) {
-            (Some(a), Self::Like) => a.to_lowercase().contains(&b.to_lowercase()),
-            _ => self.eval_option(a, b),
+}
+
+/// The compiled value of a `~` or `*=` expression.
+///
+/// Globs are translated into regular expressions.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: Regex,
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+    }
+}
+
+impl Pattern {
+    pub fn regex(s: &str) -> Result<Self, String> {
+        Regex::new(s)
+            .map(|regex| Self { regex })
+            .map_err(|e| format!("invalid regular expression: {}", e))
+    }
+    /// Build a pattern from a glob where `*` matches any string, `?` any
+    /// char, and `[...]` a char class (`[!...]` for a negated one)
+    pub fn glob(s: &str) -> Result<Self, String> {
+        let regex = glob_to_regex(s)?;
+        Regex::new(&regex)
+            .map(|regex| Self { regex })
+            .map_err(|e| format!("invalid glob: {}", e))
+    }
+    pub fn is_match(&self, s: &str) -> bool {
+        self.regex.is_match(s)
+    }
+}
+
+fn glob_to_regex(glob: &str) -> Result<String, String> {
+    let escape = |c: char| regex::escape(c.encode_utf8(&mut [0; 4]));
+    let mut regex = String::from("^");
+    let mut chars = glob.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '\\' => match chars.next() {
+                Some(c) => regex.push_str(&escape(c)),
+                None => return Err("invalid glob: trailing backslash".to_string()),
+            },
+            '[' => {
+                regex.push('[');
+                let mut first = true;
+                loop {
+                    match chars.next() {
+                        Some(']') if !first => break,
+                        Some('!' | '^') if first => regex.push('^'),
+                        Some('-') => regex.push('-'),
+                        Some(c) => regex.push_str(&escape(c)),
+                        None => return Err("invalid glob: unclosed '['".to_string()),
+                    }
+                    first = false;
+                }
+                regex.push(']');
+            }
+            c => regex.push_str(&escape(c)),
         }
     }
+    regex.push('$');
+    Ok(regex)
 }
 
 /// A leaf in the filter expression tree, an expression which
-/// may return true or false for any filesystem
+/// may return true or false for any row (by default a filesystem)
 #[derive(Debug, Clone, PartialEq)]
-pub struct ColExpr {
-    col: Col,
+pub struct ColExpr<C = Col> {
+    col: C,
     operator: ColOperator,
     value: String,
+    /// the compiled value, for pattern operators
+    pattern: Option<Pattern>,
+    /// set when a side isn't just a column or a literal
+    /// (eg `free < size*5%`), in which case it replaces col and value
+    comparison: Option<NumComparison<C>>,
+    /// the members of a `in` expression
+    members: Option<Members<C>>,
 }
 
-impl ColExpr {
+/// What a `in` expression checks
+#[derive(Debug, Clone, PartialEq)]
+pub enum Members<C> {
+    /// `type in (xfs, ext4)`: one of the equalities is true
+    List(Vec<ColExpr<C>>),
+    /// `comp_idx in 0..15`: both bounds (included) are satisfied
+    Range {
+        min: Box<ColExpr<C>>,
+        max: Box<ColExpr<C>>,
+    },
+}
+
+impl<C: TableCol> ColExpr<C> {
+    /// Tell whether the expression refers to the column
+    pub fn uses(&self, col: C) -> bool {
+        if let Some(comparison) = &self.comparison {
+            return comparison.left.uses(col) || comparison.right.uses(col);
+        }
+        match &self.members {
+            Some(Members::List(list)) => list.iter().any(|expr| expr.uses(col)),
+            Some(Members::Range { min, max }) => min.uses(col) || max.uses(col),
+            None => self.col == col,
+        }
+    }
     #[cfg(test)]
-    pub fn new<S: Into<String>>(col: Col, operator: ColOperator, value: S) -> Self {
+    pub fn new<S: Into<String>>(col: C, operator: ColOperator, value: S) -> Self {
+        let value = value.into();
+        let pattern = compile_pattern(operator, &value).unwrap();
         Self {
             col,
             operator,
-            value: value.into(),
+            value,
+            pattern,
+            comparison: None,
+            members: None,
         }
     }
-    pub fn eval(&self, mount: &Mount) -> Result<bool, EvalExprError> {
-        Ok(match self.col {
-            Col::Id => self.operator.eval(
-                mount.info.id,
-                self.value.parse::<MountId>()
-                    .map_err(|_| EvalExprError::NotAnId(self.value.to_string()))?,
-            ),
-            Col::Dev => self.operator.eval(
-                mount.info.dev,
-                self.value.parse::<DeviceId>()
-                    .map_err(|_| EvalExprError::NotADeviceId(self.value.to_string()))?,
-            ),
-            Col::Filesystem => self.operator.eval_str(
-                &mount.info.fs,
-                &self.value,
-            ),
-            Col::Label => self.operator.eval_option_str(
-                mount.fs_label.as_deref(),
-                &self.value,
-            ),
-            Col::Type => self.operator.eval_str(
-                &mount.info.fs_type,
-                &self.value,
-            ),
-            Col::Remote => self.operator.eval(
-                mount.info.is_remote(),
-                parse_bool(&self.value)?,
-            ),
-            Col::Disk => self.operator.eval_option_str(
-                mount.disk.as_ref().map(|d| d.disk_type()),
-                &self.value,
-            ),
-            Col::Used => self.operator.eval_option(
-                mount.stats().as_ref().map(|s| s.used()),
-                parse_integer(&self.value)?,
-            ),
-            Col::Use | Col::UsePercent => self.operator.eval_option(
-                mount.stats().as_ref().map(|s| s.use_share()),
-                parse_float(&self.value)?,
-            ),
-            Col::Free | Col::FreePercent => self.operator.eval_option(
-                mount.stats().as_ref().map(|s| s.available()),
-                parse_integer(&self.value)?,
-            ),
-            Col::Size => self.operator.eval_option(
-                mount.stats().as_ref().map(|s| s.size()),
-                parse_integer(&self.value)?,
-            ),
-            Col::InodesUsed => self.operator.eval_option(
-                mount.inodes().as_ref().map(|i| i.used()),
+    pub fn col(&self) -> C {
+        self.col
+    }
+    pub fn operator(&self) -> ColOperator {
+        self.operator
+    }
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+    pub fn eval(&self, row: &C::Row) -> Result<bool, EvalExprError> {
+        match &self.members {
+            Some(Members::List(list)) => {
+                for expr in list {
+                    if expr.eval(row)? {
+                        return Ok(true);
+                    }
+                }
+                return Ok(false);
+            }
+            Some(Members::Range { min, max }) => {
+                return Ok(min.eval(row)? && max.eval(row)?);
+            }
+            None => {}
+        }
+        match &self.comparison {
+            Some(comparison) => Ok(comparison.eval(self.operator, row)),
+            None => self.eval_value(&self.col.value(row)),
+        }
+    }
+    /// Evaluate the expression on the value of its column
+    pub fn eval_value(&self, value: &ColValue) -> Result<bool, EvalExprError> {
+        if self.operator.is_null_test() {
+            return Ok(value.is_missing() == (self.operator == ColOperator::IsNull));
+        }
+        Ok(match value {
+            ColValue::Int(n) | ColValue::Size(n) => self.operator.eval(
+                *n,
                 parse_integer(&self.value)?,
             ),
-            Col::InodesUse | Col::InodesUsePercent => self.operator.eval_option(
-                mount.inodes().as_ref().map(|i| i.use_share()),
+            ColValue::Float(f) => self.operator.eval(
+                *f,
                 parse_float(&self.value)?,
             ),
-            Col::InodesFree => self.operator.eval_option(
-                mount.inodes().as_ref().map(|i| i.favail),
-                parse_integer(&self.value)?,
-            ),
-            Col::InodesCount => self.operator.eval_option(
-                mount.inodes().as_ref().map(|i| i.files),
-                parse_integer(&self.value)?,
-            ),
-            Col::MountPoint => self.operator.eval_str(
-                &mount.info.mount_point.to_string_lossy(),
-                &self.value,
-            ),
-            Col::FsName => self.operator.eval_str(
-                &crate::col::extract_fsname(&mount),
-                &self.value,
-            ),
-            Col::Uuid => self.operator.eval_option_str(
-                mount.uuid.as_deref(),
-                &self.value,
-            ),
-            Col::PartUuid => self.operator.eval_option_str(
-                mount.part_uuid.as_deref(),
-                &self.value,
-            ),
-            Col::StripeCount => {
-                let mount_point_str = mount.info.mount_point.to_string_lossy();
-                if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                    if let Some(stripe_count) = lustre_info.stripe_count {
-                        self.operator.eval(
-                            stripe_count,
-                            self.value.parse::<u64>().map_err(|_| EvalExprError::NotANumber(self.value.to_string()))?,
-                        )
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            },
-            Col::StripeSize => {
-                let mount_point_str = mount.info.mount_point.to_string_lossy();
-                if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                    if let Some(stripe_size) = lustre_info.stripe_size {
-                        self.operator.eval(
-                            stripe_size,
-                            self.value.parse::<u64>().map_err(|_| EvalExprError::NotANumber(self.value.to_string()))?,
-                        )
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            },
-            Col::LustreVersion => {
-                let mount_point_str = mount.info.mount_point.to_string_lossy();
-                if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                    if let Some(lustre_version) = lustre_info.lustre_version {
-                        self.operator.eval_str(&lustre_version, &self.value)
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            },
-            Col::PoolName => {
-                let mount_point_str = mount.info.mount_point.to_string_lossy();
-                if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                    if let Some(pool_name) = lustre_info.pool_name {
-                        self.operator.eval_str(&pool_name, &self.value)
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
+            ColValue::Text(s) => self.eval_str(s),
+            ColValue::Version(v) => match self.operator {
+                ColOperator::Lower
+                | ColOperator::LowerOrEqual
+                | ColOperator::Equal
+                | ColOperator::NotEqual
+                | ColOperator::GreaterOrEqual
+                | ColOperator::Greater => self.operator.eval(
+                    version_cmp(v, &self.value),
+                    Ordering::Equal,
+                ),
+                _ => self.eval_str(v),
             },
-            Col::ComponentType => {
-                let mount_point_str = mount.info.mount_point.to_string_lossy();
-                if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                    if let Some(component_type) = lustre_info.component_type {
-                        self.operator.eval_str(&component_type, &self.value)
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            },
-            Col::ComponentIndex => {
-                let mount_point_str = mount.info.mount_point.to_string_lossy();
-                if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                    if let Some(component_index) = lustre_info.component_index {
-                        self.operator.eval(
-                            component_index,
-                            self.value.parse::<u32>().map_err(|_| EvalExprError::NotANumber(self.value.to_string()))?,
-                        )
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
+            // compared as a target when the value is a target name,
+            // so that `target < scratch-OST0010` follows the indexes
+            ColValue::Target(target) => match (self.operator, self.value.parse::<LustreTargetName>()) {
+                (
+                    ColOperator::Lower
+                    | ColOperator::LowerOrEqual
+                    | ColOperator::GreaterOrEqual
+                    | ColOperator::Greater,
+                    Ok(other),
+                ) => self.operator.eval(target, &other),
+                _ => self.eval_str(&target.to_string()),
             },
-            Col::MirrorCount => {
-                let mount_point_str = mount.info.mount_point.to_string_lossy();
-                if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                    if let Some(mirror_count) = lustre_info.mirror_count {
-                        self.operator.eval(
-                            mirror_count as u32,
-                            self.value.parse::<u32>().map_err(|_| EvalExprError::NotANumber(self.value.to_string()))?,
-                        )
-                    } else {
-                        false
-                    }
-                } else {
-                    false
+            ColValue::Bool(b) => self.operator.eval(
+                *b,
+                parse_bool(&self.value)?,
+            ),
+            ColValue::Device(dev) => self.operator.eval(
+                *dev,
+                self.value.parse::<DeviceId>()
+                    .map_err(|_| EvalExprError::NotADeviceId(self.value.to_string()))?,
+            ),
+            // a list matches when one of its items does, or, for
+            // a negative operator, when all of them do
+            ColValue::List(items) => match self.operator {
+                ColOperator::NotEqual | ColOperator::NotMatches | ColOperator::NotGlob => {
+                    items.iter().all(|item| self.eval_str(item))
                 }
+                _ => items.iter().any(|item| self.eval_str(item)),
             },
+            ColValue::Missing => false,
         })
     }
+    /// Evaluate the expression on the value of a text column
+    pub fn eval_str(&self, a: &str) -> bool {
+        match (&self.pattern, self.operator) {
+            (Some(pattern), ColOperator::NotMatches | ColOperator::NotGlob) => !pattern.is_match(a),
+            (Some(pattern), _) => pattern.is_match(a),
+            (None, operator) => operator.eval_str(a, &self.value),
+        }
+    }
+}
+
+fn compile_pattern(operator: ColOperator, value: &str) -> Result<Option<Pattern>, String> {
+    match operator {
+        ColOperator::Matches | ColOperator::NotMatches => Pattern::regex(value).map(Some),
+        ColOperator::Glob | ColOperator::NotGlob => Pattern::glob(value).map(Some),
+        _ => Ok(None),
+    }
 }
 
 #[derive(Debug)]
@@ -255,14 +322,21 @@ pub struct ParseExprError {
     pub raw: String,
     /// why
     pub message: String,
+    /// position (in chars) of the offending char in raw, when known
+    pub pos: Option<usize>,
 }
 impl ParseExprError {
     pub fn new<R: Into<String>, M: Into<String>>(raw: R, message: M) -> Self {
         Self {
             raw: raw.into(),
             message: message.into(),
+            pos: None,
         }
     }
+    pub fn at(mut self, pos: usize) -> Self {
+        self.pos = Some(pos);
+        self
+    }
 }
 impl fmt::Display for ParseExprError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -271,69 +345,369 @@ impl fmt::Display for ParseExprError {
             "{:?} can't be parsed as an expression: {}",
             self.raw,
             self.message
-        )
+        )?;
+        if let Some(pos) = self.pos {
+            // show a caret under the offending char
+            write!(f, "\n    {}\n    {}^", self.raw, " ".repeat(pos))?;
+        }
+        Ok(())
     }
 }
 impl std::error::Error for ParseExprError {}
 
-impl FromStr for ColExpr {
-    type Err = ParseExprError;
-    fn from_str(input: &str) -> Result<Self, ParseExprError> {
-        let mut chars_indices = input.char_indices();
-        let mut op_idx = 0;
-        for (idx, c) in &mut chars_indices {
-            if c == '<' || c == '>' || c == '=' {
-                op_idx = idx;
-                break;
-            }
+impl<C: TableCol> ColExpr<C> {
+    /// Build the expression from a token of the filter lexer.
+    ///
+    /// Errors come with the position of the offending part
+    pub(crate) fn from_token(token: &ExprToken) -> Result<Self, (usize, String)> {
+        if token.col.text.is_empty() {
+            return Err((token.col.pos, "expected a column name".to_string()));
         }
-        if op_idx == 0 {
-            return Err(ParseExprError::new(input, "Invalid expression; expected <column><operator><value>"));
+        let (op, operator) = OPERATORS
+            .iter()
+            .chain(KEYWORD_OPERATORS)
+            .find(|(op, _)| *op == token.operator.text)
+            .ok_or_else(|| (token.operator.pos, format!("unknown operator: {:?}", token.operator.text)))?;
+        if *operator == ColOperator::In {
+            return Self::from_in_token(token);
         }
-        let mut val_idx =  op_idx + 1;
-        for (idx, c) in &mut chars_indices {
-            if c != '<' && c != '>' && c != '=' {
-                val_idx = idx;
-                break;
+        if operator.is_null_test() {
+            if token.quoted_value || !token.value.text.eq_ignore_ascii_case("null") {
+                return Err((token.value.pos, format!("expected null after {:?}", op)));
+            }
+            if num_expr::has_arith_operator(&token.col.text) {
+                return Err((token.col.pos, "only a column can be tested for null".to_string()));
+            }
+            let col: C = token.col.text.parse()
+                .map_err(|e: ParseColError| (token.col.pos, e.to_string()))?;
+            return Ok(Self {
+                col,
+                operator: *operator,
+                value: "null".to_string(),
+                pattern: None,
+                comparison: None,
+                members: None,
+            });
+        }
+        if !num_expr::has_arith_operator(&token.col.text) {
+            let col: C = token.col.text.parse()
+                .map_err(|e: ParseColError| (token.col.pos, e.to_string()))?;
+            // numeric columns are compared as numeric expressions, so that
+            // their values are checked now and may refer to other columns
+            let is_numeric = !token.quoted_value && col.num_kind().is_some();
+            if !is_numeric {
+                if operator.is_pattern() && !col.is_text() {
+                    return Err((
+                        token.operator.pos,
+                        format!("the {} operator only applies to text columns", op),
+                    ));
+                }
+                let value = &token.value.text;
+                let pattern = compile_pattern(*operator, value)
+                    .map_err(|message| (token.value.pos, message))?;
+                return Ok(Self {
+                    col,
+                    operator: *operator,
+                    value: value.to_string(),
+                    pattern,
+                    comparison: None,
+                    members: None,
+                });
             }
         }
-        if val_idx == input.len() {
-            return Err(ParseExprError::new(input, "no value"));
+        // a comparison of numeric expressions
+        if operator.is_pattern() {
+            return Err((
+                token.operator.pos,
+                format!("the {} operator only applies to text columns", op),
+            ));
         }
-        let col = &input[..op_idx];
-        let col = col.parse()
-            .map_err(|e: ParseColError| ParseExprError::new(input, e.to_string()))?;
-        let operator = match &input[op_idx..val_idx] {
-            "<" => ColOperator::Lower,
-            "<=" => ColOperator::LowerOrEqual,
-            "=" => ColOperator::Like,
-            "==" => ColOperator::Equal,
-            "<>" => ColOperator::NotEqual,
-            ">=" => ColOperator::GreaterOrEqual,
-            ">" => ColOperator::Greater,
-            op => {
-                return Err(ParseExprError::new(
-                    input,
-                    format!("unknown operator: {:?}", op),
+        let left = NumExpr::parse(&token.col.text)
+            .map_err(|(offset, message)| (token.col.pos + offset, message))?;
+        let right = NumExpr::parse(&token.value.text)
+            .map_err(|(offset, message)| (token.value.pos + offset, message))?;
+        let comparison = NumComparison { left, right };
+        comparison.check()
+            .map_err(|message| (token.operator.pos, message))?;
+        let col = comparison.left.first_col()
+            .or_else(|| comparison.right.first_col())
+            .ok_or_else(|| (token.col.pos, "the expression doesn't refer to a column".to_string()))?;
+        Ok(Self {
+            col,
+            operator: *operator,
+            value: token.value.text.clone(),
+            pattern: None,
+            comparison: Some(comparison),
+            members: None,
+        })
+    }
+    /// Build a `in` expression, whose members are parsed like the
+    /// value of a `==` comparison (or `>=` and `<=` for the bounds
+    /// of a range)
+    fn from_in_token(token: &ExprToken) -> Result<Self, (usize, String)> {
+        let member = |operator: &str, value: Spanned, quoted_value: bool| {
+            Self::from_token(&ExprToken {
+                col: token.col.clone(),
+                operator: Spanned {
+                    text: operator.to_string(),
+                    pos: token.operator.pos,
+                },
+                value,
+                quoted_value,
+                list: None,
+            })
+        };
+        let members = if let Some(list) = &token.list {
+            let list = list
+                .iter()
+                .map(|item| member("==", item.value.clone(), item.quoted))
+                .collect::<Result<Vec<_>, _>>()?;
+            Members::List(list)
+        } else {
+            let range = Some(&token.value)
+                .filter(|_| !token.quoted_value)
+                .and_then(split_range);
+            let Some((min, max)) = range else {
+                return Err((
+                    token.value.pos,
+                    "expected a list, like (xfs, ext4), or a range, like 0..15".to_string(),
                 ));
+            };
+            Members::Range {
+                min: Box::new(member(">=", min, false)?),
+                max: Box::new(member("<=", max, false)?),
             }
         };
-        let value = &input[val_idx..];
-        let value = value.into();
-        Ok(Self { col, operator, value })
+        let col = match &members {
+            Members::List(list) => list[0].col,
+            Members::Range { min, .. } => min.col,
+        };
+        Ok(Self {
+            col,
+            operator: ColOperator::In,
+            value: token.value.text.clone(),
+            pattern: None,
+            comparison: None,
+            members: Some(members),
+        })
+    }
+}
+
+impl<C: TableCol> FromStr for ColExpr<C> {
+    type Err = ParseExprError;
+    fn from_str(input: &str) -> Result<Self, ParseExprError> {
+        let tokens = filter_lexer::lex(input)
+            .map_err(|e| ParseExprError::new(input, e.message).at(e.pos))?;
+        match tokens.as_slice() {
+            [Token::Expr(token)] => Self::from_token(token)
+                .map_err(|(pos, message)| ParseExprError::new(input, message).at(pos)),
+            _ => Err(ParseExprError::new(input, "expected a single <column><operator><value> expression")),
+        }
     }
 }
 
+/// Split a `min..max` range into its bounds, with their
+/// positions (in chars)
+fn split_range(value: &Spanned) -> Option<(Spanned, Spanned)> {
+    let text = &value.text;
+    let idx = text
+        .find("..")
+        .filter(|&idx| idx > 0 && idx + 2 < text.len())?;
+    let min = Spanned {
+        text: text[..idx].to_string(),
+        pos: value.pos,
+    };
+    let max = Spanned {
+        text: text[idx + 2..].to_string(),
+        pos: value.pos + text[..idx].chars().count() + 2,
+    };
+    Some((min, max))
+}
+
 #[test]
 fn test_col_filter_parsing() {
     assert_eq!(
         "remote=false".parse::<ColExpr>().unwrap(),
         ColExpr::new(Col::Remote, ColOperator::Like, "false"),
     );
+    let size = "size<32G".parse::<ColExpr>().unwrap();
+    assert_eq!((size.col, size.operator, size.value.as_str()), (Col::Size, ColOperator::Lower, "32G"));
+    assert!(size.comparison.is_some());
+    assert_eq!(
+        "mount~'^/scratch/'".parse::<ColExpr>().unwrap(),
+        ColExpr::new(Col::MountPoint, ColOperator::Matches, "^/scratch/"),
+    );
     assert_eq!(
-        "size<32G".parse::<ColExpr>().unwrap(),
-        ColExpr::new(Col::Size, ColOperator::Lower, "32G"),
+        "fs*=lustre-OST00*".parse::<ColExpr>().unwrap(),
+        ColExpr::new(Col::Filesystem, ColOperator::Glob, "lustre-OST00*"),
     );
+    assert_eq!(
+        "type!~'^(tmp|dev)fs$'".parse::<ColExpr>().unwrap(),
+        ColExpr::new(Col::Type, ColOperator::NotMatches, "^(tmp|dev)fs$"),
+    );
+    assert_eq!(
+        "fs!*=*_UUID".parse::<ColExpr>().unwrap(),
+        ColExpr::new(Col::Filesystem, ColOperator::NotGlob, "*_UUID"),
+    );
+}
+
+#[test]
+fn test_col_filter_parsing_errors() {
+    let message = |input: &str| input.parse::<ColExpr>().unwrap_err().message;
+    assert!(message("mount~[a-").starts_with("invalid regular expression"));
+    assert!(message("fs*=lustre-OST[0").starts_with("invalid glob"));
+    assert_eq!(message("size~^1"), "the ~ operator only applies to text columns");
+    assert_eq!(message("size=>3"), "unknown operator: \"=>\"");
+    assert_eq!(message("size<"), "no value");
+    assert_eq!(message("=3"), "expected a column name");
+    assert_eq!(message("type=xfs|type=ext4"), "expected a single <column><operator><value> expression");
+}
+
+#[test]
+fn test_numeric_comparisons() {
+    use crate::{job_col::JobCol, jobstats::JobStat, test_util::job};
+    let job = JobStat {
+        read_bytes: 3000,
+        write_bytes: 1000,
+        metadata_ops: 12,
+        ..job("dd.1000", "lustre-OST0000")
+    };
+    let check = |input: &str| input.parse::<ColExpr<JobCol>>().unwrap().eval(&job).unwrap();
+    assert!(check("read>2K"));
+    assert!(check("read > write*2"));
+    assert!(!check("read > write*3"));
+    assert!(check("read == write*3"));
+    assert!(check("write < read - 1K"));
+    assert!(check("write/read < 50%"));
+    assert!(check("ops*100 >= write"));
+    assert!(check("write*3 >= read"));
+    let message = |input: &str| input.parse::<ColExpr<JobCol>>().unwrap_err().message;
+    assert_eq!(message("read > 20%"), "can't compare an amount with a percentage");
+    assert_eq!(message("read > write+20%"), "can't add or subtract an amount and a percentage");
+    assert_eq!(message("read > job"), "\"job\" isn't a numeric column");
+    assert_eq!(message("read > wirte"), "\"wirte\" can't be parsed as a column; use 'dysk --list-cols' to see all column names");
+    assert_eq!(message("read > 12X"), "\"12X\" can't be evaluated as a number");
+    assert_eq!(message("read+write ~ 3"), "the ~ operator only applies to text columns");
+    let e = "read > write*wirte".parse::<ColExpr<JobCol>>().unwrap_err();
+    assert_eq!(e.pos, Some(13));
+}
+
+#[test]
+fn test_membership_and_null_tests() {
+    use crate::{job_col::JobCol, jobstats::JobStat, test_util::job};
+    let job = JobStat {
+        read_bytes: 3000,
+        write_bytes: 1000,
+        metadata_ops: 12,
+        ..job("dd.1000", "lustre-OST0003")
+    };
+    let check = |input: &str| input.parse::<ColExpr<JobCol>>().unwrap().eval(&job).unwrap();
+    assert!(check("target in (lustre-OST0001, lustre-OST0003)"));
+    assert!(check("target IN ('lustre-OST0003')"));
+    assert!(!check("target in (lustre-OST0001, lustre-OST0002)"));
+    assert!(!check("target in (OST0003)")); // members are exact
+    assert!(check("ops in (10, 12, 14)"));
+    assert!(check("ops in 10..12"));
+    assert!(check("ops in 12..20"));
+    assert!(!check("ops in 13..20"));
+    assert!(check("read in 2K..write*3"));
+    assert!(check("target is not null"));
+    assert!(!check("target is null"));
+    assert!(check("read is not null"));
+    let message = |input: &str| input.parse::<ColExpr<JobCol>>().unwrap_err().message;
+    assert_eq!(message("ops in 12"), "expected a list, like (xfs, ext4), or a range, like 0..15");
+    assert_eq!(message("ops in 12.."), "expected a list, like (xfs, ext4), or a range, like 0..15");
+    assert_eq!(message("ops in (1, 20%)"), "can't compare an amount with a percentage");
+    assert_eq!(message("target is empty"), "expected null after \"is\"");
+    assert_eq!(message("read+write is null"), "only a column can be tested for null");
+}
+
+#[test]
+fn test_null_tests_on_mounts() {
+    let mount = crate::test_util::mount(10, "/mnt/test-null")
+        .fs_type("xfs")
+        .uuid("1234")
+        .row();
+    let check = |input: &str| input.parse::<ColExpr>().unwrap().eval(&mount).unwrap();
+    assert!(check("label is null"));
+    assert!(!check("label is not null"));
+    assert!(check("uuid is not null"));
+    assert!(check("pool is null"));
+    assert!(check("size is null"));
+    assert!(!check("remote is null"));
+    assert!(check("type in (ext4, xfs)"));
+    assert!(!check("label in (data, home)"));
+}
+
+#[test]
+fn test_split_range() {
+    let spanned = |text: &str, pos| Spanned { text: text.to_string(), pos };
+    assert_eq!(
+        split_range(&spanned("0..15", 10)),
+        Some((spanned("0", 10), spanned("15", 13))),
+    );
+    assert_eq!(
+        split_range(&spanned("é..ü", 3)),
+        Some((spanned("é", 3), spanned("ü", 6))),
+    );
+    assert_eq!(split_range(&spanned("..15", 0)), None);
+    assert_eq!(split_range(&spanned("15", 0)), None);
+}
+
+#[test]
+fn test_version_comparisons() {
+    let version = ColValue::Version("2.15.3".to_string());
+    let check = |input: &str| input.parse::<ColExpr>().unwrap().eval_value(&version).unwrap();
+    assert!(check("lustre_version > 2.9"));
+    assert!(check("lustre_version >= 2.15.3"));
+    assert!(check("lustre_version == 2.15.3.0"));
+    assert!(!check("lustre_version < 2.15"));
+    assert!(check("lustre_version = 2.15"));
+    assert!(check("lustre_version *= 2.15.*"));
+}
+
+#[test]
+fn test_target_comparisons() {
+    use crate::lustre_target::TargetKind;
+    let target = ColValue::Target(LustreTargetName::new("scratch", TargetKind::Ost, 0x10000));
+    let check = |input: &str| input.parse::<ColExpr>().unwrap().eval_value(&target).unwrap();
+    assert!(check("target > scratch-OST2000"));
+    assert!(!check("target < scratch-OST2000"));
+    assert!(check("target = scratch-OST10000"));
+    assert!(check("target *= *-OST1*"));
+}
+
+#[test]
+fn test_parse_error_position() {
+    let e = "size>3G & kind=lustre".parse::<crate::filter::Filter>().unwrap_err();
+    assert_eq!(e.pos, Some(10));
+    assert_eq!(
+        e.to_string(),
+        "\"size>3G & kind=lustre\" can't be parsed as an expression: \"kind\" can't be parsed as a column; use 'dysk --list-cols' to see all column names\n    size>3G & kind=lustre\n              ^",
+    );
+    let e = "mount~'(unclosed'".parse::<crate::filter::Filter>().unwrap_err();
+    assert_eq!(e.pos, Some(6));
+    assert!(e.message.starts_with("invalid regular expression"));
+}
+
+#[test]
+fn test_pattern_matching() {
+    let check = |input: &str, value: &str| input.parse::<ColExpr>().unwrap().eval_str(value);
+    assert!(check("mount~^/scratch/", "/scratch/project"));
+    assert!(!check("mount~^/scratch/", "/home/scratch/"));
+    assert!(check("mount!~^/scratch/", "/home/scratch/"));
+    assert!(check("fs*=lustre-OST00*", "lustre-OST0001_UUID"));
+    assert!(!check("fs*=lustre-OST00*", "lustre-MDT0000_UUID"));
+    assert!(check("fs!*=lustre-OST00*", "lustre-MDT0000_UUID"));
+    assert!(check("fs*=lustre-?DT000[0-3]_UUID", "lustre-MDT0002_UUID"));
+    assert!(!check("fs*=lustre-OST000[!0-3]*", "lustre-OST0002_UUID"));
+    assert!(check("fs*=lustre-OST000[!0-3]*", "lustre-OST0009_UUID"));
+    // globs are anchored and their special chars are literal in the regex
+    assert!(!check("fs*=OST", "lustre-OST0000"));
+    assert!(check("fs*='a.b(c)'", "a.b(c)"));
+    assert!(!check("fs*='a.b(c)'", "axb(c)"));
+    assert!(check("fs*=a\\*", "a*"));
+    assert!(!check("fs*=a\\*", "ab"));
 }
 
 #[derive(Debug, PartialEq)]
@@ -344,8 +718,6 @@ pub enum EvalExprError {
     NotADeviceId(String),
     NotABool(String),
 }
-impl EvalExprError {
-}
 impl fmt::Display for EvalExprError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -366,7 +738,7 @@ impl fmt::Display for EvalExprError {
 }
 impl std::error::Error for EvalExprError {}
 
-fn parse_bool(input: &str) -> Result<bool, EvalExprError> {
+pub(crate) fn parse_bool(input: &str) -> Result<bool, EvalExprError> {
     let s = input.to_lowercase();
     match s.as_ref() {
         "x" | "t" | "true" | "1" | "y" | "yes" => Ok(true),
@@ -376,7 +748,7 @@ fn parse_bool(input: &str) -> Result<bool, EvalExprError> {
 }
 
 /// Parse numbers like "1234", "32G", "4kB", "54Gib", "1.2M"
-fn parse_integer(input: &str) -> Result<u64, EvalExprError> {
+pub(crate) fn parse_integer(input: &str) -> Result<u64, EvalExprError> {
     let s = input.to_lowercase();
     let s = s.trim_end_matches('b');
     let (s, binary) = match s.strip_suffix('i') {
@@ -418,7 +790,7 @@ fn test_parse_integer(){
 }
 
 /// parse numbers like "0.25", "50%"
-fn parse_float(input: &str) -> Result<f64, EvalExprError> {
+pub(crate) fn parse_float(input: &str) -> Result<f64, EvalExprError> {
     let s = input.to_lowercase();
     let (s, percent) = match s.strip_suffix('%') {
         Some(s) => (s, true),
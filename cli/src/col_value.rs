@@ -0,0 +1,142 @@
+use {
+    crate::{
+        lustre_target::LustreTargetName,
+        natural::{natural_cmp, version_cmp},
+        units::Units,
+    },
+    lfs_core::DeviceId,
+    serde_json::{json, Value},
+    std::cmp::Ordering,
+};
+
+/// The typed value of a column for a row.
+///
+/// Sorting, filtering, and all outputs are computed from it,
+/// so that a column behaves the same everywhere.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColValue {
+    /// a count, an id, or an index
+    Int(u64),
+    /// a share, between 0 and 1
+    Float(f64),
+    /// a number of bytes
+    Size(u64),
+    Text(String),
+    /// a version, like `2.15.3`, compared segment by segment
+    Version(String),
+    Bool(bool),
+    Device(DeviceId),
+    /// a MDT or OST, ordered by filesystem, kind, then index
+    Target(LustreTargetName),
+    /// several texts, like the mount points of a filesystem
+    List(Vec<String>),
+    /// the value isn't known for this row
+    Missing,
+}
+
+impl ColValue {
+    pub fn int<N: Into<u64>>(n: Option<N>) -> Self {
+        n.map_or(Self::Missing, |n| Self::Int(n.into()))
+    }
+    pub fn float(f: Option<f64>) -> Self {
+        f.map_or(Self::Missing, Self::Float)
+    }
+    pub fn size(n: Option<u64>) -> Self {
+        n.map_or(Self::Missing, Self::Size)
+    }
+    pub fn text<S: Into<String>>(s: Option<S>) -> Self {
+        s.map_or(Self::Missing, |s| Self::Text(s.into()))
+    }
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Self::Missing)
+    }
+    /// The value as a number, for arithmetic expressions
+    pub fn number(&self) -> Option<f64> {
+        match self {
+            Self::Int(n) | Self::Size(n) => Some(*n as f64),
+            Self::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+    /// Compare two values of the same column. A missing value
+    /// is lower than any other one
+    pub fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Int(a), Self::Int(b)) | (Self::Size(a), Self::Size(b)) => a.cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            (Self::Text(a), Self::Text(b)) => a.cmp(b),
+            (Self::Version(a), Self::Version(b)) => version_cmp(a, b),
+            (Self::Bool(a), Self::Bool(b)) => a.cmp(b),
+            (Self::Device(a), Self::Device(b)) => a.cmp(b),
+            (Self::Target(a), Self::Target(b)) => a.cmp(b),
+            (Self::List(a), Self::List(b)) => a.cmp(b),
+            (Self::Missing, Self::Missing) => Ordering::Equal,
+            (Self::Missing, _) => Ordering::Less,
+            (_, Self::Missing) => Ordering::Greater,
+            _ => Ordering::Equal,
+        }
+    }
+    /// Compare two values of the same column, texts being compared
+    /// in natural order (`OST2` before `OST10`)
+    pub fn compare_natural(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Text(a), Self::Text(b)) => natural_cmp(a, b),
+            _ => self.compare(other),
+        }
+    }
+    /// The value as displayed in textual outputs, None when missing.
+    ///
+    /// Shares are shown as percentages, sizes with the given units
+    pub fn display(&self, units: Units) -> Option<String> {
+        match self {
+            Self::Int(n) => Some(n.to_string()),
+            Self::Float(f) => Some(format!("{:.0}%", 100.0 * f)),
+            Self::Size(n) => Some(units.fmt(*n)),
+            Self::Text(s) | Self::Version(s) => Some(s.clone()),
+            Self::Bool(b) => Some(if *b { "yes" } else { "no" }.to_string()),
+            Self::Device(dev) => Some(format!("{}:{}", dev.major, dev.minor)),
+            Self::Target(target) => Some(target.to_string()),
+            Self::List(items) => Some(items.join(", ")),
+            Self::Missing => None,
+        }
+    }
+    /// The raw value, for JSON
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Int(n) | Self::Size(n) => json!(n),
+            Self::Float(f) => json!(f),
+            Self::Text(s) | Self::Version(s) => json!(s),
+            Self::Bool(b) => json!(b),
+            Self::Device(dev) => json!(format!("{}:{}", dev.major, dev.minor)),
+            Self::Target(target) => json!(target.to_string()),
+            Self::List(items) => json!(items),
+            Self::Missing => Value::Null,
+        }
+    }
+}
+
+#[test]
+fn test_col_value_comparison() {
+    use ColValue::*;
+    assert_eq!(Size(3).compare(&Size(12)), Ordering::Less);
+    assert_eq!(Float(0.5).compare(&Float(0.25)), Ordering::Greater);
+    assert_eq!(Missing.compare(&Int(0)), Ordering::Less);
+    assert_eq!(Text("a".to_string()).compare(&Missing), Ordering::Greater);
+    assert_eq!(Missing.compare(&Missing), Ordering::Equal);
+    let text = |s: &str| Text(s.to_string());
+    assert_eq!(text("OST10").compare(&text("OST2")), Ordering::Less);
+    assert_eq!(text("OST10").compare_natural(&text("OST2")), Ordering::Greater);
+    let version = |s: &str| Version(s.to_string());
+    assert_eq!(version("2.15.3").compare(&version("2.9.1")), Ordering::Greater);
+    let dev = |major, minor| Device(DeviceId { major, minor });
+    assert_eq!(dev(8, 2).compare(&dev(10, 0)), Ordering::Less);
+}
+
+#[test]
+fn test_target_comparison() {
+    use crate::lustre_target::TargetKind;
+    let ost = |index| ColValue::Target(LustreTargetName::new("scratch", TargetKind::Ost, index));
+    assert_eq!(ost(0x2000).compare(&ost(0x10000)), Ordering::Less);
+    assert_eq!(ost(0x10000).display(Units::Si).as_deref(), Some("scratch-OST10000"));
+    assert_eq!(ost(2).compare_natural(&ost(10)), Ordering::Less);
+}
@@ -67,9 +67,63 @@ impl Cols {
     }
 }
 
+/// A named set of columns
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColSet {
+    pub name: String,
+    pub cols: Vec<Col>,
+}
+
+/// The named sets of columns which can be used in `--cols`:
+/// the built-in ones and the ones defined in configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColSets(Vec<ColSet>);
+
+impl Default for ColSets {
+    fn default() -> Self {
+        Self(
+            BUILTIN_COL_SETS
+                .iter()
+                .map(|&(name, cols)| ColSet {
+                    name: name.to_string(),
+                    cols: cols.to_vec(),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl ColSets {
+    pub fn get(&self, name: &str) -> Option<&ColSet> {
+        self.0.iter().find(|set| set.name == name)
+    }
+    /// Add a set, replacing the one of the same name
+    pub fn insert(&mut self, set: ColSet) {
+        match self.0.iter_mut().find(|s| s.name == set.name) {
+            Some(s) => *s = set,
+            None => self.0.push(set),
+        }
+    }
+    pub fn sets(&self) -> &[ColSet] {
+        &self.0
+    }
+}
+
 impl FromStr for Cols {
     type Err = ParseColError;
     fn from_str(value: &str) -> Result<Self, ParseColError> {
+        Self::parse_with(value, &Self::default(), &ColSets::default())
+    }
+}
+
+impl Cols {
+    /// Parse a columns definition, eg `+dev-size` or `fs+ids`,
+    /// in which the names of the sets can be used like columns.
+    ///
+    /// The base is the set of the default columns of the view: it's
+    /// the one modified when the definition starts or ends with a `+`
+    /// or a `-`, and the one named `default`
+    pub fn parse_with(value: &str, base: &Cols, sets: &ColSets) -> Result<Self, ParseColError> {
         let value = value.trim();
         let mut tokens: Vec<String> = Vec::new();
         let mut must_create = true;
@@ -91,12 +145,12 @@ impl FromStr for Cols {
             if first_token == "+" || first_token == "-" {
                 // if it starts with an addition or removal, the
                 // default set is implied
-                Cols::default()
+                base.clone()
             } else {
                 Cols::empty()
             }
         } else {
-            return Ok(Self::default());
+            return Ok(base.clone());
         };
         let mut negative = false;
         for token in &tokens {
@@ -121,29 +175,39 @@ impl FromStr for Cols {
                 }
                 "default" => {
                     if negative {
-                        cols.remove_set(DEFAULT_COLS);
+                        cols.remove_set(base.cols());
                         negative = false;
                     } else {
-                        cols.add_set(DEFAULT_COLS);
+                        cols.add_set(base.cols());
                     }
                 }
-                _ => {
-                    let col: Col = token.parse()?;
-                    if negative {
-                        cols.remove(col);
-                        negative = false;
-                    } else {
-                        cols.add(col);
+                name => match sets.get(name) {
+                    Some(set) => {
+                        if negative {
+                            cols.remove_set(&set.cols);
+                            negative = false;
+                        } else {
+                            cols.add_set(&set.cols);
+                        }
                     }
-                }
+                    None => {
+                        let col: Col = name.parse()?;
+                        if negative {
+                            cols.remove(col);
+                            negative = false;
+                        } else {
+                            cols.add(col);
+                        }
+                    }
+                },
             }
         }
         match tokens.last().map(|s| s.as_ref()) {
             Some("-") => {
-                cols.remove_set(DEFAULT_COLS);
+                cols.remove_set(base.cols());
             }
             Some("+") => {
-                cols.add_set(DEFAULT_COLS);
+                cols.add_set(base.cols());
             }
             _ => {}
         }
@@ -201,7 +265,7 @@ mod cols_parsing {
     fn algebraic_cols() {
         check(
             "all - dev -inodes + label",
-            vec![Id, Filesystem, Type, Remote, Disk, Used, Use, UsePercent, Free, FreePercent, Size, InodesUsed, InodesUsePercent, InodesFree, InodesCount, MountPoint, Uuid, PartUuid, Label],
+            vec![Id, Filesystem, Type, Remote, Disk, Used, Use, UsePercent, Free, FreePercent, Size, InodesUsed, InodesUsePercent, InodesFree, InodesCount, MountPoint, Mounts, FsName, Uuid, PartUuid, StripeCount, StripeSize, LustreVersion, PoolName, ComponentType, ComponentIndex, Target, MirrorCount, HsmState, HsmArchiveId, HsmCoordinator, HsmPending, HsmActive, Label],
         );
         check(
             "dev + dev +disk - use + size",
@@ -209,19 +273,19 @@ mod cols_parsing {
         );
         check(
             "all-default+use",
-            vec![Id, Dev, Label, Remote, UsePercent, FreePercent, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, Uuid, PartUuid, Use],
+            vec![Id, Dev, Label, Type, Remote, Disk, UsePercent, FreePercent, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, MountPoint, Mounts, Uuid, PartUuid, StripeCount, StripeSize, LustreVersion, PoolName, ComponentType, ComponentIndex, Target, MirrorCount, HsmState, HsmArchiveId, HsmCoordinator, HsmPending, HsmActive, Use],
         );
         check(
             "all+default", // special: all but default at the end
-            vec![Id, Dev, Label, Remote, UsePercent, FreePercent, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, Uuid, PartUuid, Filesystem, Type, Disk, Used, Use, Free, Size, MountPoint]
+            vec![Id, Dev, Label, Type, Remote, Disk, UsePercent, FreePercent, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, MountPoint, Mounts, Uuid, PartUuid, StripeCount, StripeSize, LustreVersion, PoolName, ComponentType, ComponentIndex, Target, MirrorCount, HsmState, HsmArchiveId, HsmCoordinator, HsmPending, HsmActive, Filesystem, Used, Use, Free, Size, FsName]
         );
         check(
             "fs dev all", // we want all column but fs and dev at the start
-            vec![Filesystem, Dev, Id, Label, Type, Remote, Disk, Used, Use, UsePercent, Free, FreePercent, Size, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, MountPoint, Uuid, PartUuid],
+            vec![Filesystem, Dev, Id, Label, Type, Remote, Disk, Used, Use, UsePercent, Free, FreePercent, Size, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, MountPoint, Mounts, FsName, Uuid, PartUuid, StripeCount, StripeSize, LustreVersion, PoolName, ComponentType, ComponentIndex, Target, MirrorCount, HsmState, HsmArchiveId, HsmCoordinator, HsmPending, HsmActive],
         );
         check(
             "fs dev all -id-disk",
-            vec![Filesystem, Dev, Label, Type, Remote, Used, Use, UsePercent, Free, FreePercent, Size, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, MountPoint, Uuid, PartUuid],
+            vec![Filesystem, Dev, Label, Type, Remote, Used, Use, UsePercent, Free, FreePercent, Size, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, MountPoint, Mounts, FsName, Uuid, PartUuid, StripeCount, StripeSize, LustreVersion, PoolName, ComponentType, ComponentIndex, Target, MirrorCount, HsmState, HsmArchiveId, HsmCoordinator, HsmPending, HsmActive],
         );
     }
 
@@ -249,52 +313,83 @@ mod cols_parsing {
         );
         check(
             "+dev",
-            vec![Filesystem, Type, Disk, Used, Use, Free, Size, MountPoint, Dev]
+            vec![Filesystem, Used, Use, Free, Size, FsName, Dev]
         );
         check(
             "dev+",
-            vec![Dev, Filesystem, Type, Disk, Used, Use, Free, Size, MountPoint]
+            vec![Dev, Filesystem, Used, Use, Free, Size, FsName]
         );
         check(
             "all-",
-            vec![Id, Dev, Label, Remote, UsePercent, FreePercent, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, Uuid, PartUuid],
+            vec![Id, Dev, Label, Type, Remote, Disk, UsePercent, FreePercent, InodesUsed, InodesUse, InodesUsePercent, InodesFree, InodesCount, MountPoint, Mounts, Uuid, PartUuid, StripeCount, StripeSize, LustreVersion, PoolName, ComponentType, ComponentIndex, Target, MirrorCount, HsmState, HsmArchiveId, HsmCoordinator, HsmPending, HsmActive],
         );
         check(
             "-size+inodes_free+",
-            vec![Filesystem, Type, Disk, Used, Use, Free, MountPoint, InodesFree, Size]
+            vec![Filesystem, Used, Use, Free, FsName, InodesFree, Size]
         );
         check(
             "+dev-size+inodes_use",
-            vec![Filesystem, Type, Disk, Used, Use, Free, MountPoint, Dev, InodesUse]
+            vec![Filesystem, Used, Use, Free, FsName, Dev, InodesUse]
         );
         check(
             "-use-type",
-            vec![Filesystem, Disk, Used, Free, Size, MountPoint]
+            vec![Filesystem, Used, Free, Size, FsName]
         );
         check(
             "default+dev",
-            vec![Filesystem, Type, Disk, Used, Use, Free, Size, MountPoint, Dev]
+            vec![Filesystem, Used, Use, Free, Size, FsName, Dev]
         );
         check(
             "default,size+use", // just reordering
-            vec![Filesystem, Type, Disk, Used, Free, MountPoint, Size, Use]
+            vec![Filesystem, Used, Free, FsName, Size, Use]
         );
         check(
             "dev default",
-            vec![Dev, Filesystem, Type, Disk, Used, Use, Free, Size, MountPoint]
+            vec![Dev, Filesystem, Used, Use, Free, Size, FsName]
         );
         check(
             "size dev default -disk",
-            vec![Size, Dev, Filesystem, Type, Used, Use, Free, MountPoint]
+            vec![Size, Dev, Filesystem, Used, Use, Free, FsName]
         );
         check(
             "default-fs+inodes",
-            vec![Type, Disk, Used, Use, Free, Size, MountPoint, InodesUse]
+            vec![Used, Use, Free, Size, FsName, InodesUse]
         );
         check(
             "+inodes_used+inodes_free",
-            vec![Filesystem, Type, Disk, Used, Use, Free, Size, MountPoint, InodesUsed, InodesFree]
+            vec![Filesystem, Used, Use, Free, Size, FsName, InodesUsed, InodesFree]
+        );
+    }
+
+    #[test]
+    fn col_sets() {
+        check(
+            "fs+ids",
+            vec![Filesystem, Id, Dev, Uuid, PartUuid],
+        );
+        check(
+            "fs inodes_all size",
+            vec![Filesystem, InodesUsed, InodesUse, InodesFree, InodesCount, Size],
+        );
+        // a column is never shadowed by a set
+        check(
+            "+dev+inodes",
+            vec![Filesystem, Used, Use, Free, Size, FsName, Dev, InodesUse],
+        );
+        for (name, _) in BUILTIN_COL_SETS {
+            assert!(name.parse::<Col>().is_err(), "{name} is a column");
+        }
+        check(
+            "all-lustre-ids-inodes_all",
+            vec![Filesystem, Label, Type, Remote, Disk, Used, Use, UsePercent, Free, FreePercent, Size, InodesUsePercent, MountPoint, Mounts, FsName, ComponentType, ComponentIndex, Target, HsmState, HsmArchiveId, HsmCoordinator, HsmPending, HsmActive],
+        );
+        let mut sets = ColSets::default();
+        sets.insert(ColSet { name: "mine".to_string(), cols: vec![Type, MountPoint] });
+        assert_eq!(
+            Cols::parse_with("fs+mine", &Cols::default(), &sets).unwrap(),
+            Cols::new([Filesystem, Type, MountPoint]),
         );
+        assert!("fs+mine".parse::<Cols>().is_err());
     }
 
 }
@@ -0,0 +1,398 @@
+use {
+    crate::{
+        args::{Args, TriBool},
+        col::Col,
+        cols::{ColSet, ColSets, Cols},
+        units::Units,
+        view::View,
+    },
+    clap::{
+        parser::ValueSource,
+        ArgMatches,
+        ValueEnum,
+    },
+    serde::Deserialize,
+    std::{
+        collections::BTreeMap,
+        env,
+        error,
+        fmt,
+        fs,
+        io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// Path of the system-wide configuration file
+pub const SYSTEM_CONF_PATH: &str = "/etc/dysk/conf.toml";
+
+/// The defaults of a view
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ViewConf {
+    pub cols: Option<String>,
+    pub sort: Option<String>,
+    pub filter: Option<String>,
+}
+
+/// The configuration, giving defaults to the arguments which
+/// aren't set on the command line.
+///
+/// The standard view is configured at the root of the file,
+/// the Lustre view in its `[lustre]` section. Units, colors,
+/// and ASCII rendering apply to both.
+///
+/// Named views are defined in the `[views]` section, eg `[views.capacity]`,
+/// and sets of columns in the `[col_sets]` one.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Conf {
+    pub cols: Option<String>,
+    pub sort: Option<String>,
+    pub filter: Option<String>,
+    pub units: Option<String>,
+    /// `auto`, `yes`, or `no`
+    pub color: Option<String>,
+    pub ascii: Option<bool>,
+    #[serde(default)]
+    pub lustre: ViewConf,
+    #[serde(default)]
+    pub views: BTreeMap<String, View>,
+    /// definitions of column sets, by name
+    #[serde(default)]
+    pub col_sets: BTreeMap<String, String>,
+}
+
+impl ViewConf {
+    /// Overwrite the settings with the ones defined in other
+    pub fn merge(&mut self, other: Self) {
+        merge_opt(&mut self.cols, other.cols);
+        merge_opt(&mut self.sort, other.sort);
+        merge_opt(&mut self.filter, other.filter);
+    }
+}
+
+impl Conf {
+    /// Path of the user configuration file, in `$XDG_CONFIG_HOME`
+    /// or, when not defined, in `~/.config`
+    pub fn user_path() -> Option<PathBuf> {
+        env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+            .map(|dir| dir.join("dysk").join("conf.toml"))
+    }
+    /// Read the system configuration then the user one, whose
+    /// settings have precedence. Missing files are ignored
+    pub fn load() -> Result<Self, ConfError> {
+        let mut conf = Self::read(Path::new(SYSTEM_CONF_PATH))?.unwrap_or_default();
+        if let Some(path) = Self::user_path() {
+            if let Some(user_conf) = Self::read(&path)? {
+                conf.merge(user_conf);
+            }
+        }
+        Ok(conf)
+    }
+    /// Read a configuration file, None if there's none at this path
+    pub fn read(path: &Path) -> Result<Option<Self>, ConfError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(ConfError::new(format!("Can't read {:?}", path), e)),
+        };
+        content
+            .parse()
+            .map(Some)
+            .map_err(|e| ConfError::new(format!("Invalid configuration in {:?}", path), e))
+    }
+    /// Overwrite the settings with the ones defined in other
+    pub fn merge(&mut self, other: Self) {
+        merge_opt(&mut self.cols, other.cols);
+        merge_opt(&mut self.sort, other.sort);
+        merge_opt(&mut self.filter, other.filter);
+        merge_opt(&mut self.units, other.units);
+        merge_opt(&mut self.color, other.color);
+        merge_opt(&mut self.ascii, other.ascii);
+        self.lustre.merge(other.lustre);
+        self.views.extend(other.views);
+        self.col_sets.extend(other.col_sets);
+    }
+    /// The built-in sets of columns and the ones of the configuration,
+    /// which may be defined with the built-in ones
+    pub fn col_sets(&self) -> Result<ColSets, ConfError> {
+        let builtins = ColSets::default();
+        let mut sets = builtins.clone();
+        for (name, def) in &self.col_sets {
+            if name.is_empty() || !name.chars().all(|c| c.is_alphabetic() || c == '_') {
+                return Err(ConfError::new(
+                    format!("Invalid column set name {:?}", name),
+                    "only letters and '_' are allowed",
+                ));
+            }
+            if name.parse::<Col>().is_ok() {
+                return Err(ConfError::new(
+                    format!("Invalid column set name {:?}", name),
+                    "it's the name of a column",
+                ));
+            }
+            let cols = Cols::parse_with(def, &Cols::default(), &builtins)
+                .map_err(|e| ConfError::new(format!("Invalid column set {:?}", name), e))?;
+            sets.insert(ColSet {
+                name: name.clone(),
+                cols: cols.0,
+            });
+        }
+        Ok(sets)
+    }
+    /// The views, the ones of the configuration replacing
+    /// the built-in ones of the same name
+    pub fn views(&self) -> BTreeMap<String, View> {
+        let mut views = View::builtins();
+        views.extend(self.views.clone());
+        views
+    }
+    pub fn view(&self, name: &str) -> Result<View, ConfError> {
+        let mut views = self.views();
+        views.remove(name).ok_or_else(|| {
+            let names: Vec<&str> = views.keys().map(String::as_str).collect();
+            ConfError::new(
+                format!("Unknown view {:?}", name),
+                format!("available views are {}", names.join(", ")),
+            )
+        })
+    }
+    /// Build the arguments of the standard view and the ones of the
+    /// Lustre view. A setting comes from the command line when given
+    /// there, else from the named view if any, else from the
+    /// configuration, else from the built-in defaults
+    pub fn resolve(
+        &self,
+        mut args: Args,
+        matches: &ArgMatches,
+    ) -> Result<(Args, Args), ConfError> {
+        let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        let view = match args.take_view_name() {
+            Some(name) => Some(self.view(&name)?),
+            None => None,
+        };
+        if let Some(units) = self.units.as_ref().filter(|_| !given("units")) {
+            args.units = units
+                .parse::<Units>()
+                .map_err(|e| ConfError::new("Invalid units in configuration", e))?;
+        }
+        if let Some(color) = self.color.as_ref().filter(|_| !given("color")) {
+            args.color = TriBool::from_str(color, true)
+                .map_err(|e| ConfError::new("Invalid color in configuration", e))?;
+        }
+        if let Some(ascii) = self.ascii.filter(|_| !given("ascii")) {
+            args.ascii = ascii;
+        }
+        args.cols_given = given("cols");
+        let mut lustre_args = args.clone();
+        if !given("cols") {
+            args.cols_def = self.cols.clone();
+            lustre_args.cols_def = self.lustre.cols.clone();
+        }
+        if !given("sort") {
+            args.sort = self.sort.clone();
+            lustre_args.sort = self.lustre.sort.clone();
+        }
+        if !given("filter") {
+            args.filter = self.filter.clone();
+            lustre_args.filter = self.lustre.filter.clone();
+        }
+        if let Some(view) = view {
+            view.apply(&mut args, given);
+            view.apply(&mut lustre_args, given);
+        }
+        // a definition starting or ending with `+` or `-` modifies
+        // the default columns, which are the same in both views
+        let col_sets = self.col_sets()?;
+        let view_cols = |def: &Option<String>| {
+            parse_cols(def.as_deref().unwrap_or(""), &Cols::default(), &col_sets)
+        };
+        args.cols = view_cols(&args.cols_def)?;
+        lustre_args.cols = view_cols(&lustre_args.cols_def)?;
+        Ok((args, lustre_args))
+    }
+}
+
+impl std::str::FromStr for Conf {
+    type Err = toml::de::Error;
+    fn from_str(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+}
+
+fn merge_opt<T>(dst: &mut Option<T>, src: Option<T>) {
+    if src.is_some() {
+        *dst = src;
+    }
+}
+
+fn parse_cols(def: &str, base: &Cols, col_sets: &ColSets) -> Result<Cols, ConfError> {
+    Cols::parse_with(def, base, col_sets)
+        .map_err(|e| ConfError::new("Invalid columns", e))
+}
+
+#[derive(Debug)]
+pub struct ConfError {
+    context: String,
+    reason: String,
+}
+impl ConfError {
+    pub fn new<S: Into<String>, E: ToString>(context: S, reason: E) -> Self {
+        Self {
+            context: context.into(),
+            reason: reason.to_string(),
+        }
+    }
+}
+impl fmt::Display for ConfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.reason)
+    }
+}
+impl error::Error for ConfError {}
+
+#[cfg(test)]
+mod conf_tests {
+    use {
+        super::*,
+        crate::format::Format,
+        clap::{CommandFactory, FromArgMatches},
+    };
+
+    fn resolve(conf: &Conf, cli: &[&str]) -> (Args, Args) {
+        let matches = Args::command()
+            .try_get_matches_from(std::iter::once("dysk").chain(cli.iter().copied()))
+            .unwrap();
+        let args = Args::from_arg_matches(&matches).unwrap();
+        conf.resolve(args, &matches).unwrap()
+    }
+
+    #[test]
+    fn parse_conf() {
+        let conf: Conf = r#"
+            cols = "fs+type+size"
+            units = "binary"
+            color = "no"
+            ascii = true
+
+            [lustre]
+            sort = "use-desc"
+        "#.parse().unwrap();
+        assert_eq!(conf.cols.as_deref(), Some("fs+type+size"));
+        assert_eq!(conf.units.as_deref(), Some("binary"));
+        assert_eq!(conf.ascii, Some(true));
+        assert_eq!(conf.sort, None);
+        assert_eq!(conf.lustre.sort.as_deref(), Some("use-desc"));
+        assert!("colums = \"fs\"".parse::<Conf>().is_err());
+    }
+
+    #[test]
+    fn user_conf_overrides_system_conf() {
+        let mut conf: Conf = "units = \"binary\"\nfilter = \"size>1G\"".parse().unwrap();
+        conf.merge("units = \"bytes\"\n[lustre]\ncols = \"fs+size\"".parse().unwrap());
+        assert_eq!(conf.units.as_deref(), Some("bytes"));
+        assert_eq!(conf.filter.as_deref(), Some("size>1G"));
+        assert_eq!(conf.lustre.cols.as_deref(), Some("fs+size"));
+    }
+
+    #[test]
+    fn command_line_overrides_conf() {
+        let conf: Conf = r#"
+            cols = "fs+type"
+            sort = "type"
+            units = "binary"
+            color = "no"
+            [lustre]
+            filter = "use>50%"
+        "#.parse().unwrap();
+        let (args, lustre_args) = resolve(&conf, &[]);
+        assert_eq!(args.cols, Cols::new([Col::Filesystem, Col::Type]));
+        assert_eq!(args.sort.as_deref(), Some("type"));
+        assert_eq!(args.units, Units::Binary);
+        assert_eq!(args.color, TriBool::No);
+        assert_eq!(args.serialized_cols(), None);
+        assert_eq!(lustre_args.cols, Cols::default());
+        assert_eq!(lustre_args.sort, None);
+        assert_eq!(lustre_args.filter.as_deref(), Some("use>50%"));
+        assert_eq!(lustre_args.units, Units::Binary);
+        let (args, lustre_args) = resolve(&conf, &["-c", "size", "-u", "SI", "-f", "free>1G"]);
+        assert_eq!(args.cols, Cols::new([Col::Size]));
+        assert_eq!(lustre_args.cols, Cols::new([Col::Size]));
+        assert_eq!(args.serialized_cols(), Some(&Cols::new([Col::Size])));
+        assert_eq!(args.sort.as_deref(), Some("type"));
+        assert_eq!(args.units, Units::Si);
+        assert_eq!(lustre_args.filter.as_deref(), Some("free>1G"));
+        let (args, _) = resolve(&conf, &["-c", "all"]);
+        assert_eq!(args.serialized_cols(), None);
+        let (args, _) = resolve(&Conf::default(), &[]);
+        assert_eq!(args.units, Units::Si);
+        assert_eq!(args.color, TriBool::Auto);
+    }
+
+    #[test]
+    fn col_modifiers_apply_to_the_view_defaults() {
+        let (args, lustre_args) = resolve(&Conf::default(), &["-c", "+stripe_count-free"]);
+        assert_eq!(
+            args.cols,
+            Cols::new([Col::Filesystem, Col::Used, Col::Use, Col::Size, Col::FsName, Col::StripeCount]),
+        );
+        assert_eq!(
+            lustre_args.cols,
+            Cols::new([Col::Filesystem, Col::Used, Col::Use, Col::Size, Col::FsName, Col::StripeCount]),
+        );
+        let conf: Conf = "[lustre]\ncols = \"+pool\"".parse().unwrap();
+        let (_, lustre_args) = resolve(&conf, &[]);
+        assert_eq!(
+            lustre_args.cols,
+            Cols::new([Col::Filesystem, Col::Used, Col::Use, Col::Free, Col::Size, Col::FsName, Col::PoolName]),
+        );
+    }
+
+    #[test]
+    fn col_modifiers_and_defaults_have_the_same_base() {
+        let col_sets = Conf::default().col_sets().unwrap();
+        let (args, lustre_args) = resolve(&Conf::default(), &[]);
+        assert_eq!(parse_cols("", &Cols::default(), &col_sets).unwrap(), Cols::default());
+        assert_eq!(args.cols, Cols::default());
+        assert_eq!(lustre_args.cols, Cols::default());
+        let mut with_dev = Cols::default();
+        with_dev.add(Col::Dev);
+        assert_eq!(parse_cols("+dev", &Cols::default(), &col_sets).unwrap(), with_dev);
+        let (args, _) = resolve(&Conf::default(), &["-c", "+dev"]);
+        assert_eq!(args.cols, with_dev);
+    }
+
+    #[test]
+    fn named_views() {
+        let conf: Conf = r#"
+            sort = "type"
+            [views.capacity]
+            cols = "fs+size"
+            filter = "size > 1T"
+            format = "json"
+            [views.remote]
+            filter = "remote = yes & type = nfs4"
+        "#.parse().unwrap();
+        let (args, lustre_args) = resolve(&conf, &["@capacity"]);
+        assert_eq!(args.path, None);
+        assert_eq!(args.cols, Cols::new([Col::Filesystem, Col::Size]));
+        assert_eq!(args.filter.as_deref(), Some("size > 1T"));
+        assert_eq!(args.sort.as_deref(), Some("type"));
+        assert_eq!(args.format(), Format::Json);
+        assert_eq!(lustre_args.cols, Cols::new([Col::Filesystem, Col::Size]));
+        let (args, _) = resolve(&conf, &["--view", "capacity", "-c", "fs+used", "--csv", "/home"]);
+        assert_eq!(args.cols, Cols::new([Col::Filesystem, Col::Used]));
+        assert_eq!(args.format(), Format::Csv);
+        assert_eq!(args.path.as_deref(), Some(std::path::Path::new("/home")));
+        // a view of the configuration replaces the built-in one
+        let (args, _) = resolve(&conf, &["@remote"]);
+        assert_eq!(args.filter.as_deref(), Some("remote = yes & type = nfs4"));
+        let (args, _) = resolve(&conf, &["@full-disks"]);
+        assert_eq!(args.sort.as_deref(), Some("use-desc"));
+        assert!(conf.view("nope").is_err());
+    }
+}
@@ -1,8 +1,14 @@
 use {
     crate::{
         Args, col::Col,
+        changelog::ChangelogUser,
+        col_value::ColValue,
+        job_col::{JobCol, ALL_JOB_COLS},
+        jobstats::JobStat,
+        mount_row::MountRow,
+        server_targets::ServerTarget,
+        totals::Totals,
     },
-    lfs_core::*,
     std::{
         fmt::Display,
         io::Write,
@@ -49,8 +55,7 @@ impl<W: Write> Csv<W> {
     }
 }
 
-pub fn print(mounts: &[&Mount], args: &Args) -> Result<(), std::io::Error> {
-    let units = args.units;
+pub fn print(mounts: &[&MountRow], args: &Args) -> Result<(), std::io::Error> {
     let inodes_mode = args.inodes;
     let mut csv = Csv::new(args.csv_separator, std::io::stdout());
     
@@ -60,122 +65,54 @@ pub fn print(mounts: &[&Mount], args: &Args) -> Result<(), std::io::Error> {
     csv.end_line()?;
     
     for mount in mounts {
-        for col in args.cols.cols() {
+        for &col in args.cols.cols() {
+            cell_value(&mut csv, col, col.displayed_value(mount, inodes_mode), args)?;
+        }
+        csv.end_line()?;
+    }
+    if args.total {
+        print_totals(&mut csv, &Totals::compute(mounts), args)?;
+    }
+    Ok(())
+}
+
+/// Write the value of a column, the usage columns with a bar
+/// in the table being written as plain shares
+fn cell_value<W: Write>(csv: &mut Csv<W>, col: Col, value: ColValue, args: &Args) -> Result<(), std::io::Error> {
+    match (col, value) {
+        (Col::Use | Col::InodesUse, ColValue::Float(share)) => csv.cell(share),
+        (_, value) => csv.cell_opt(value.display(args.units)),
+    }
+}
+
+/// Write the `--total` row, with "total" in the first textual column
+fn print_totals<W: Write>(csv: &mut Csv<W>, totals: &Totals, args: &Args) -> Result<(), std::io::Error> {
+    let label_col = Totals::label_col(args.cols.cols());
+    for &col in args.cols.cols() {
+        if Some(col) == label_col {
+            csv.cell("total")?;
+        } else {
+            cell_value(csv, col, totals.value(col, args.inodes), args)?;
+        }
+    }
+    csv.end_line()
+}
+
+/// Print the jobs view as CSV
+pub fn print_jobs(jobs: &[&JobStat], args: &Args) -> Result<(), std::io::Error> {
+    let mut csv = Csv::new(args.csv_separator, std::io::stdout());
+    for col in ALL_JOB_COLS {
+        csv.cell(col.title())?;
+    }
+    csv.end_line()?;
+    for job in jobs {
+        for col in ALL_JOB_COLS {
             match col {
-                Col::Id => csv.cell(mount.info.id),
-                Col::Dev => csv.cell(format!("{}:{}", mount.info.dev.major, mount.info.dev.minor)),
-                Col::Filesystem => csv.cell(&mount.info.fs),
-                Col::Label => csv.cell_opt(mount.fs_label.as_ref()),
-                Col::Type => csv.cell(&mount.info.fs_type),
-                Col::Remote => csv.cell(if mount.info.is_remote() { "yes" } else { "no" }),
-                Col::Disk => csv.cell_opt(mount.disk.as_ref().map(|d| d.disk_type())),
-                Col::Used => {
-                    if inodes_mode {
-                        csv.cell_opt(mount.inodes().map(|i| i.used()))
-                    } else {
-                        csv.cell_opt(mount.stats().map(|s| units.fmt(s.used())))
-                    }
-                },
-                Col::Use => {
-                    if inodes_mode {
-                        csv.cell_opt(mount.inodes().map(|i| i.use_share()))
-                    } else {
-                        csv.cell_opt(mount.stats().map(|s| s.use_share()))
-                    }
-                },
-                Col::UsePercent => {
-                    if inodes_mode {
-                        csv.cell_opt(mount.inodes().map(|i| format!("{:.0}%", 100.0 * i.use_share())))
-                    } else {
-                        csv.cell_opt(mount.stats().map(|s| format!("{:.0}%", 100.0 * s.use_share())))
-                    }
-                },
-                Col::Free => {
-                    if inodes_mode {
-                        csv.cell_opt(mount.inodes().map(|i| i.favail))
-                    } else {
-                        csv.cell_opt(mount.stats().map(|s| units.fmt(s.available())))
-                    }
-                },
-                Col::FreePercent => {
-                    if inodes_mode {
-                        csv.cell_opt(mount.inodes().map(|i| format!("{:.0}%", 100.0 * (1.0 - i.use_share()))))
-                    } else {
-                        csv.cell_opt(mount.stats().map(|s| format!("{:.0}%", 100.0 * (1.0 - s.use_share()))))
-                    }
-                },
-                Col::Size => {
-                    if inodes_mode {
-                        csv.cell_opt(mount.inodes().map(|i| i.files))
-                    } else {
-                        csv.cell_opt(mount.stats().map(|s| units.fmt(s.size())))
-                    }
-                },
-                Col::InodesUsed => csv.cell_opt(mount.inodes().map(|i| i.used())),
-                Col::InodesUse => csv.cell_opt(mount.inodes().map(|i| i.use_share())),
-                Col::InodesUsePercent => csv.cell_opt(mount.inodes().map(|i| format!("{:.0}%", 100.0 * i.use_share()))),
-                Col::InodesFree => csv.cell_opt(mount.inodes().map(|i| i.favail)),
-                Col::InodesCount => csv.cell_opt(mount.inodes().map(|i| i.files)),
-                Col::MountPoint => csv.cell(mount.info.mount_point.to_string_lossy()),
-                Col::FsName => csv.cell(crate::col::extract_fsname(&mount)),
-                Col::Uuid => csv.cell(mount.uuid.as_ref().map_or("", |v| v)),
-                Col::PartUuid => csv.cell(mount.part_uuid.as_ref().map_or("", |v| v)),
-                Col::StripeCount => {
-                    let mount_point_str = mount.info.mount_point.to_string_lossy();
-                    if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                        csv.cell_opt(lustre_info.stripe_count.map(|c| c.to_string()))
-                    } else {
-                        csv.cell("")
-                    }
-                },
-                Col::StripeSize => {
-                    let mount_point_str = mount.info.mount_point.to_string_lossy();
-                    if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                        csv.cell_opt(lustre_info.stripe_size.map(|s| s.to_string()))
-                    } else {
-                        csv.cell("")
-                    }
-                },
-                Col::LustreVersion => {
-                    let mount_point_str = mount.info.mount_point.to_string_lossy();
-                    if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                        csv.cell(lustre_info.lustre_version.as_deref().unwrap_or(""))
-                    } else {
-                        csv.cell("")
-                    }
-                },
-                Col::PoolName => {
-                    let mount_point_str = mount.info.mount_point.to_string_lossy();
-                    if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                        csv.cell(lustre_info.pool_name.as_deref().unwrap_or(""))
-                    } else {
-                        csv.cell("")
-                    }
-                },
-                Col::ComponentType => {
-                    let mount_point_str = mount.info.mount_point.to_string_lossy();
-                    if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                        csv.cell(lustre_info.component_type.as_deref().unwrap_or(""))
-                    } else {
-                        csv.cell("")
-                    }
-                },
-                Col::ComponentIndex => {
-                    let mount_point_str = mount.info.mount_point.to_string_lossy();
-                    if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                        csv.cell_opt(lustre_info.component_index.map(|i| i.to_string()))
-                    } else {
-                        csv.cell("")
-                    }
-                },
-                Col::MirrorCount => {
-                    let mount_point_str = mount.info.mount_point.to_string_lossy();
-                    if let Some(lustre_info) = crate::get_lustre_info(&mount_point_str) {
-                        csv.cell_opt(lustre_info.mirror_count.map(|m| m.to_string()))
-                    } else {
-                        csv.cell("")
-                    }
-                },
+                JobCol::JobId => csv.cell(&job.job_id),
+                JobCol::Target => csv.cell(&job.target),
+                JobCol::ReadBytes => csv.cell(args.units.fmt(job.read_bytes)),
+                JobCol::WriteBytes => csv.cell(args.units.fmt(job.write_bytes)),
+                JobCol::MetadataOps => csv.cell(job.metadata_ops),
             }?;
         }
         csv.end_line()?;
@@ -183,6 +120,54 @@ pub fn print(mounts: &[&Mount], args: &Args) -> Result<(), std::io::Error> {
     Ok(())
 }
 
+/// Print the MDT and OST served by this node as CSV
+pub fn print_server_targets(targets: &[ServerTarget], args: &Args) -> Result<(), std::io::Error> {
+    let mut csv = Csv::new(args.csv_separator, std::io::stdout());
+    for title in [
+        "target", "kind", "device", "fs type", "recovery", "completed clients",
+        "total clients", "exports", "read only", "size", "free", "mount point",
+    ] {
+        csv.cell(title)?;
+    }
+    csv.end_line()?;
+    for target in targets {
+        let recovery = target.recovery.as_ref();
+        csv.cell(&target.name)?;
+        csv.cell(target.name.kind)?;
+        csv.cell_opt(target.device.as_ref())?;
+        csv.cell_opt(target.backend)?;
+        csv.cell_opt(recovery.map(|r| &r.status))?;
+        csv.cell_opt(recovery.and_then(|r| r.completed_clients))?;
+        csv.cell_opt(recovery.and_then(|r| r.total_clients))?;
+        csv.cell_opt(target.exports)?;
+        csv.cell_opt(target.read_only.map(|ro| if ro { "yes" } else { "no" }))?;
+        csv.cell_opt(target.size.map(|s| args.units.fmt(s)))?;
+        csv.cell_opt(target.available.map(|s| args.units.fmt(s)))?;
+        csv.cell_opt(target.mount_point.as_ref().map(|p| p.to_string_lossy()))?;
+        csv.end_line()?;
+    }
+    Ok(())
+}
+
+/// Print the changelog users of the local MDTs as CSV
+pub fn print_changelog_users(users: &[ChangelogUser], args: &Args) -> Result<(), std::io::Error> {
+    let mut csv = Csv::new(args.csv_separator, std::io::stdout());
+    for title in ["MDT", "user", "last cleared", "current index", "lag", "idle seconds"] {
+        csv.cell(title)?;
+    }
+    csv.end_line()?;
+    for user in users {
+        csv.cell(&user.mdt)?;
+        csv.cell(&user.id)?;
+        csv.cell(user.last_cleared)?;
+        csv.cell(user.current_index)?;
+        csv.cell(user.lag())?;
+        csv.cell_opt(user.idle)?;
+        csv.end_line()?;
+    }
+    Ok(())
+}
+
 #[test]
 fn test_csv() {
     use std::io::Cursor;
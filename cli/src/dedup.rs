@@ -0,0 +1,187 @@
+use {
+    crate::mount_row::MountRow,
+    clap::ValueEnum,
+    lfs_core::Mount,
+    std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+    },
+};
+
+/// How the mounts of a same filesystem are merged into one row
+#[derive(ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dedup {
+    /// every mount has its own row
+    None,
+    /// the mounts of a same device (eg bind mounts) are merged
+    Device,
+    /// the mounts of a same filesystem source (a device or an
+    /// export) are merged, the other ones by device
+    Fs,
+}
+
+/// The key of the group of the mount, None when it's never merged.
+///
+/// The Lustre targets are always kept separate, as each one is a
+/// different component of the filesystem, while the bind mounts of
+/// a Lustre client are merged like the other ones.
+fn group_key(mount: &Mount, dedup: Dedup) -> Option<String> {
+    if mount.info.fs_type == "lustre" && crate::is_lustre_component_mount(mount) {
+        return None;
+    }
+    let device_key = || format!("{}:{}", mount.info.dev.major, mount.info.dev.minor);
+    match dedup {
+        Dedup::None => None,
+        Dedup::Device => Some(device_key()),
+        Dedup::Fs if is_filesystem_source(&mount.info.fs) => Some(mount.info.fs.clone()),
+        // virtual filesystems (eg tmpfs or proc) have a source
+        // which doesn't tell them apart
+        Dedup::Fs => Some(device_key()),
+    }
+}
+
+/// Whether the source of a mount identifies its filesystem: a
+/// device (eg `/dev/sda1`) or an export (eg `server:/export`)
+fn is_filesystem_source(source: &str) -> bool {
+    source.starts_with('/') || source.contains(":/")
+}
+
+/// Keep only one row per group of rows of the same filesystem,
+/// with the mount points of the group.
+///
+/// The representative is the first mount whose mount point is in
+/// `preferred`, else the shortest mount point (the root one first).
+///
+/// Each kept row takes the place of the first row of its group,
+/// so that the order only depends on the order of the given rows.
+pub fn deduplicate(rows: Vec<MountRow>, dedup: Dedup, preferred: &[PathBuf]) -> Vec<MountRow> {
+    let mut groups: Vec<Vec<MountRow>> = Vec::new();
+    let mut group_indexes: HashMap<String, usize> = HashMap::new();
+    for row in rows {
+        let key = group_key(&row, dedup);
+        match key.as_ref().and_then(|key| group_indexes.get(key)) {
+            Some(&idx) => groups[idx].push(row),
+            None => {
+                if let Some(key) = key {
+                    group_indexes.insert(key, groups.len());
+                }
+                groups.push(vec![row]);
+            }
+        }
+    }
+    let mut deduplicated = Vec::with_capacity(groups.len());
+    for group in groups {
+        if group.len() == 1 {
+            deduplicated.extend(group);
+            continue;
+        }
+        let mut mount_points: Vec<PathBuf> = group
+            .iter()
+            .flat_map(|row| row.mount_points.iter().cloned())
+            .collect();
+        let mut representative = choose_representative_mount(group, preferred);
+        // the representative's mount point comes first
+        mount_points.sort_by_key(|path| path != &representative.info.mount_point);
+        representative.mount_points = mount_points;
+        deduplicated.push(representative);
+    }
+    deduplicated
+}
+
+/// Choose the best representative mount from a group of mounts for the same filesystem
+fn choose_representative_mount(mut mounts: Vec<MountRow>, preferred: &[PathBuf]) -> MountRow {
+    if let Some(pos) = mounts
+        .iter()
+        .position(|m| preferred.iter().any(|p| same_path(p, &m.info.mount_point)))
+    {
+        return mounts.remove(pos);
+    }
+
+    // prefer the mount with the shortest path (usually the root mount)
+    mounts.sort_by(|a, b| {
+        let a_path = a.info.mount_point.to_string_lossy();
+        let b_path = b.info.mount_point.to_string_lossy();
+
+        // Prefer root mounts
+        match (a_path.as_ref(), b_path.as_ref()) {
+            ("/", _) => std::cmp::Ordering::Less,
+            (_, "/") => std::cmp::Ordering::Greater,
+            _ => a_path.len().cmp(&b_path.len()).then_with(|| a_path.cmp(&b_path))
+        }
+    });
+
+    mounts.into_iter().next().unwrap()
+}
+
+/// Compare paths without being fooled by a trailing slash
+fn same_path(a: &Path, b: &Path) -> bool {
+    a.components().eq(b.components())
+}
+
+#[test]
+fn test_deduplicate() {
+    let mount = |id, minor, fs: &str, mount_point: &str| {
+        crate::test_util::mount(id, mount_point).dev(253, minor).fs(fs).row()
+    };
+    let mounts = || vec![
+        mount(1, 1, "/dev/dm-1", "/srv/dedup-test/data"),
+        mount(2, 1, "/dev/dm-1", "/srv/dedup-test"),
+        mount(3, 2, "/dev/dm-1", "/srv/dedup-test/other"),
+        mount(4, 3, "/dev/dm-3", "/srv/dedup-test/alone"),
+    ];
+    let ids = |mounts: &[MountRow]| -> Vec<u32> {
+        mounts.iter().map(|m| m.info.id as u32).collect()
+    };
+    assert_eq!(ids(&deduplicate(mounts(), Dedup::None, &[])), vec![1, 2, 3, 4]);
+    let kept = deduplicate(mounts(), Dedup::Device, &[]);
+    assert_eq!(ids(&kept), vec![2, 3, 4]);
+    let mut reversed = mounts();
+    reversed.reverse();
+    let kept = deduplicate(reversed, Dedup::Device, &[]);
+    assert_eq!(ids(&kept), vec![4, 3, 2]);
+    let kept = deduplicate(mounts(), Dedup::Fs, &[PathBuf::from("/srv/dedup-test/other/")]);
+    assert_eq!(ids(&kept), vec![3, 4]);
+    let other = kept.iter().find(|m| m.info.id == 3).unwrap();
+    assert_eq!(
+        other.mount_points,
+        vec![
+            PathBuf::from("/srv/dedup-test/other"),
+            PathBuf::from("/srv/dedup-test/data"),
+            PathBuf::from("/srv/dedup-test"),
+        ],
+    );
+    let alone = kept.iter().find(|m| m.info.id == 4).unwrap();
+    assert_eq!(alone.mount_points, vec![PathBuf::from("/srv/dedup-test/alone")]);
+    // merged rows don't leak into the next deduplication
+    let kept = deduplicate(mounts(), Dedup::None, &[]);
+    assert!(kept.iter().all(|row| row.mount_points.len() == 1));
+}
+
+#[test]
+fn test_deduplicate_virtual_and_lustre_mounts() {
+    let mount = |id, major, minor, fs: &str, fs_type: &str, mount_point: &str| {
+        crate::test_util::mount(id, mount_point)
+            .dev(major, minor)
+            .fs(fs)
+            .fs_type(fs_type)
+            .row()
+    };
+    let mounts = || vec![
+        mount(1, 0, 30, "tmpfs", "tmpfs", "/srv/dedup-test/run"),
+        mount(2, 0, 31, "tmpfs", "tmpfs", "/srv/dedup-test/tmp"),
+        mount(3, 0, 31, "tmpfs", "tmpfs", "/srv/dedup-test/tmp-bind"),
+        mount(4, 0, 50, "10.0.0.1@tcp:/scratch", "lustre", "/srv/dedup-test/scratch"),
+        mount(5, 0, 50, "10.0.0.1@tcp:/scratch", "lustre", "/srv/dedup-test/scratch-bind"),
+        mount(6, 0, 50, "scratch-OST0000_UUID", "lustre", "/srv/dedup-test/scratch"),
+        mount(7, 0, 50, "scratch-OST0001_UUID", "lustre", "/srv/dedup-test/scratch"),
+    ];
+    let ids = |rows: &[MountRow]| -> Vec<lfs_core::MountId> {
+        rows.iter().map(|row| row.info.id).collect()
+    };
+    for dedup in [Dedup::Device, Dedup::Fs] {
+        let kept = deduplicate(mounts(), dedup, &[]);
+        assert_eq!(ids(&kept), vec![1, 2, 4, 6, 7], "with {dedup:?}");
+        assert_eq!(kept[2].mount_points.len(), 2);
+    }
+}
@@ -1,9 +1,10 @@
 use {
     crate::{
+        col::{Col, TableCol},
         col_expr::*,
+        filter_lexer::{self, Token},
     },
     bet::*,
-    lfs_core::*,
     std::{
         str::FromStr,
     },
@@ -16,17 +17,24 @@ enum BoolOperator {
     Not,
 }
 
-#[derive(Debug, Default, Clone)]
-pub struct Filter {
-    expr: BeTree<BoolOperator, ColExpr>,
+/// A filter on the rows of a view, by default on filesystems
+#[derive(Debug, Clone)]
+pub struct Filter<C = Col> {
+    expr: BeTree<BoolOperator, ColExpr<C>>,
 }
 
-impl Filter {
+impl<C: TableCol> Default for Filter<C> {
+    fn default() -> Self {
+        Self { expr: BeTree::new() }
+    }
+}
+
+impl<C: TableCol> Filter<C> {
     #[allow(clippy::match_like_matches_macro)]
-    pub fn eval(&self, mount: &Mount) -> Result<bool, EvalExprError> {
+    pub fn eval(&self, row: &C::Row) -> Result<bool, EvalExprError> {
         self.expr.eval_faillible(
             // leaf evaluation
-            |col_expr| col_expr.eval(mount),
+            |col_expr| col_expr.eval(row),
             // bool operation
             |op, a, b| match (op, b) {
                 (BoolOperator::And, Some(b)) => Ok(a & b),
@@ -42,40 +50,81 @@ impl Filter {
             },
         ).map(|b| b.unwrap_or(true))
     }
-    pub fn filter<'m>(&self, mounts: &'m[Mount]) -> Result<Vec<&'m Mount>, EvalExprError> {
+    /// Tell whether the filter refers to the column
+    pub fn uses(&self, col: C) -> bool {
+        // the mapping is stopped at the first expression using the column
+        self.expr
+            .try_map_atoms(|expr| if expr.uses(col) { Err(()) } else { Ok(()) })
+            .is_err()
+    }
+    pub fn filter<'r>(&self, rows: &'r[C::Row]) -> Result<Vec<&'r C::Row>, EvalExprError> {
         let mut filtered = Vec::new();
-        for mount in mounts {
-            if self.eval(mount)? {
-                filtered.push(mount);
+        for row in rows {
+            if self.eval(row)? {
+                filtered.push(row);
             }
         }
         Ok(filtered)
     }
 }
 
-impl FromStr for Filter {
+impl<C: TableCol> FromStr for Filter<C> {
     type Err = ParseExprError;
     fn from_str(input: &str) -> Result<Self, ParseExprError> {
 
-        // we start by reading the global structure
-        let mut expr: BeTree<BoolOperator, String> = BeTree::new();
-        for c in input.chars() {
-            match c {
-                '&' => expr.push_operator(BoolOperator::And),
-                '|' => expr.push_operator(BoolOperator::Or),
-                '!' => expr.push_operator(BoolOperator::Not),
-                ' ' => {},
-                '(' => expr.open_par(),
-                ')' => expr.close_par(),
-                _ => expr.mutate_or_create_atom(String::new).push(c),
+        let tokens = filter_lexer::lex(input)
+            .map_err(|e| ParseExprError::new(input, e.message).at(e.pos))?;
+        let mut expr = BeTree::new();
+        for token in &tokens {
+            match token {
+                Token::And => expr.push_operator(BoolOperator::And),
+                Token::Or => expr.push_operator(BoolOperator::Or),
+                Token::Not => expr.push_operator(BoolOperator::Not),
+                Token::OpenPar => expr.open_par(),
+                Token::ClosePar => expr.close_par(),
+                Token::Expr(token) => {
+                    let col_expr = ColExpr::from_token(token)
+                        .map_err(|(pos, message)| ParseExprError::new(input, message).at(pos))?;
+                    expr.push_atom(col_expr);
+                }
             }
         }
 
-        // then we parse each leaf
-        let expr = expr.try_map_atoms(|raw| raw.parse())?;
-
         Ok(Self { expr })
     }
 }
 
 
+
+#[test]
+fn test_negated_match_operators() {
+    use crate::{job_col::JobCol, test_util::job};
+    let jobs = vec![
+        job("rsync.0", "lustre-OST0000"),
+        job("dd.1000", "lustre-OST0001"),
+        job("dd.1000", "lustre-MDT0000"),
+    ];
+    let filter: Filter<JobCol> = "job !~ ^rsync & !target*=*MDT*".parse().unwrap();
+    let kept = filter.filter(&jobs).unwrap();
+    assert_eq!(kept, vec![&jobs[1]]);
+    let filter: Filter<JobCol> = "target!*=*OST0000 & !(job~^dd)".parse().unwrap();
+    assert!(filter.filter(&jobs).unwrap().is_empty());
+}
+
+#[test]
+fn test_filter_uses() {
+    let filter: Filter = "hsm_state = released | (free < size*5% & type in (ext4, xfs))".parse().unwrap();
+    for col in [Col::HsmState, Col::Free, Col::Size, Col::Type] {
+        assert!(filter.uses(col), "{col:?} should be used");
+    }
+    assert!(!filter.uses(Col::HsmArchiveId));
+    assert!(!Filter::<Col>::default().uses(Col::HsmState));
+}
+
+#[test]
+fn test_misplaced_operators_are_parse_errors() {
+    for input in ["type=xfs &", "& type=xfs", "!", "()", "type=xfs | (size<1G &)", "type=xfs type=ext4"] {
+        let err = input.parse::<Filter>().unwrap_err();
+        assert!(err.pos.is_some(), "no position for {input:?}");
+    }
+}
@@ -0,0 +1,696 @@
+use {
+    crate::col_expr::OPERATORS,
+};
+
+/// Chars which can't be in an unquoted column name or value
+static SPECIAL_CHARS: &[char] = &['&', '|', '(', ')', '\'', '"'];
+
+/// Chars an operator may be made of
+static OPERATOR_CHARS: &[char] = &['<', '>', '=', '!', '~', '*'];
+
+/// Chars ending the left side of an expression (which may contain
+/// a `*` when it's an arithmetic expression)
+static COMPARISON_CHARS: &[char] = &['<', '>', '=', '!', '~'];
+
+/// Chars around which whitespace may be found in an unquoted value,
+/// like in `use_percent + 20%` or `1G .. 10G`
+static JOINING_CHARS: &[char] = &['+', '-', '*', '/', '.'];
+
+/// A part of an expression, with the position (in chars) of
+/// its start in the filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned {
+    pub text: String,
+    pub pos: usize,
+}
+
+/// A value of a `in (...)` list
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListItem {
+    pub value: Spanned,
+    pub quoted: bool,
+}
+
+/// A `<column><operator><value>` expression, whose value
+/// has been unquoted.
+///
+/// Both sides may also be arithmetic expressions, like in
+/// `free < size*5%`.
+///
+/// The operator may also be a keyword: `in`, followed by a list
+/// of values or a range, or `is` and `is not`, followed by `null`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExprToken {
+    pub col: Spanned,
+    pub operator: Spanned,
+    pub value: Spanned,
+    /// whether the value was quoted, and so is a literal
+    pub quoted_value: bool,
+    /// the values of a `in (...)` list
+    pub list: Option<Vec<ListItem>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    And,
+    Or,
+    Not,
+    OpenPar,
+    ClosePar,
+    Expr(ExprToken),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    /// position (in chars) of the offending char
+    pub pos: usize,
+    pub message: String,
+}
+
+impl LexError {
+    fn new<S: Into<String>>(pos: usize, message: S) -> Self {
+        Self {
+            pos,
+            message: message.into(),
+        }
+    }
+}
+
+/// Split a filter into tokens.
+///
+/// Whitespace is ignored outside quotes, as are the quotes around
+/// values, but it can't separate two parts of an unquoted value
+/// unless one of them is an arithmetic or range operator. The tokens
+/// must make a valid boolean expression. In quoted values, a backslash escapes the quote and
+/// the backslash, other backslashes are kept (so that regular
+/// expressions don't need double escaping).
+pub fn lex(input: &str) -> Result<Vec<Token>, LexError> {
+    Lexer {
+        chars: input.chars().collect(),
+        pos: 0,
+    }
+    .tokens()
+}
+
+#[derive(Clone)]
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Lexer {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+    fn at(&self, s: &str) -> bool {
+        s.chars().enumerate().all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+    /// Tell whether the given keyword starts at the current position
+    /// (case insensitively), followed by a whitespace, a `(`, or the end
+    fn at_keyword(&self, keyword: &str) -> bool {
+        let len = keyword.chars().count();
+        keyword
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i).is_some_and(|&k| k.eq_ignore_ascii_case(&c)))
+            && !matches!(self.chars.get(self.pos + len), Some(&c) if !c.is_whitespace() && c != '(')
+    }
+    /// Skip whitespace and tell whether a keyword operator follows
+    fn skip_to_keyword(&mut self) -> bool {
+        let start = self.pos;
+        self.skip_whitespace();
+        if self.at_keyword("in") || self.at_keyword("is") {
+            return true;
+        }
+        self.pos = start;
+        false
+    }
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+    fn tokens(mut self) -> Result<Vec<Token>, LexError> {
+        let mut tokens = Vec::new();
+        let mut open_pars = Vec::new();
+        // whether the next token must start an operand (an expression,
+        // a '(' or a '!') rather than be a '&', a '|' or a ')'
+        let mut expect_operand = true;
+        loop {
+            self.skip_whitespace();
+            let Some(c) = self.peek() else {
+                break;
+            };
+            let starts_operand = !matches!(c, '&' | '|' | ')');
+            if starts_operand && !expect_operand {
+                return Err(LexError::new(self.pos, "expected '&' or '|' between expressions"));
+            }
+            if !starts_operand && expect_operand {
+                return Err(LexError::new(self.pos, format!("expected an expression before {:?}", c)));
+            }
+            let token = match c {
+                '&' => Token::And,
+                '|' => Token::Or,
+                '!' => Token::Not,
+                '(' => {
+                    open_pars.push(self.pos);
+                    Token::OpenPar
+                }
+                ')' => {
+                    if open_pars.pop().is_none() {
+                        return Err(LexError::new(self.pos, "unexpected ')'"));
+                    }
+                    Token::ClosePar
+                }
+                '\'' | '"' => {
+                    return Err(LexError::new(self.pos, "expected a column name"));
+                }
+                _ => {
+                    tokens.push(Token::Expr(self.expr()?));
+                    expect_operand = false;
+                    continue;
+                }
+            };
+            expect_operand = !matches!(token, Token::ClosePar);
+            self.pos += 1;
+            tokens.push(token);
+        }
+        if let Some(pos) = open_pars.pop() {
+            return Err(LexError::new(pos, "unclosed '('"));
+        }
+        if expect_operand && !tokens.is_empty() {
+            return Err(LexError::new(self.pos, "expected an expression"));
+        }
+        Ok(tokens)
+    }
+    /// Skip whitespace found in an unquoted value, checking it's between
+    /// parts that can be joined
+    fn skip_value_whitespace(&mut self) -> Result<(), LexError> {
+        let before = self.pos.checked_sub(1).and_then(|i| self.chars.get(i).copied());
+        self.skip_whitespace();
+        match (before, self.peek()) {
+            (Some(b), Some(a)) if !JOINING_CHARS.contains(&b) && !JOINING_CHARS.contains(&a) => {
+                Err(LexError::new(
+                    self.pos,
+                    "unexpected space in a value; quote the value or separate expressions with '&' or '|'",
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+    fn expr(&mut self) -> Result<ExprToken, LexError> {
+        // the column, or an arithmetic expression on columns
+        let col_pos = self.pos;
+        let mut col = String::new();
+        while let Some(c) = self.peek() {
+            if SPECIAL_CHARS.contains(&c) || COMPARISON_CHARS.contains(&c) || self.at("*=") {
+                break;
+            }
+            if c.is_whitespace() {
+                if !col.is_empty() && self.skip_to_keyword() {
+                    return self.keyword_expr(Spanned { text: col, pos: col_pos });
+                }
+            } else {
+                col.push(c);
+            }
+            self.pos += 1;
+        }
+        // the operator
+        let op_pos = self.pos;
+        if !self.peek().is_some_and(|c| OPERATOR_CHARS.contains(&c)) {
+            return Err(LexError::new(
+                op_pos,
+                "expected an operator; expressions look like <column><operator><value>",
+            ));
+        }
+        let rest: String = self.chars[op_pos..].iter().collect();
+        let Some((op, col_operator)) = OPERATORS.iter().find(|(op, _)| rest.starts_with(op)) else {
+            let op_run: String = rest.chars().take_while(|c| OPERATOR_CHARS.contains(c)).collect();
+            return Err(LexError::new(op_pos, format!("unknown operator: {:?}", op_run)));
+        };
+        // a glob or regex may start with a comparison char, other unquoted values can't
+        if !col_operator.is_pattern() && rest[op.len()..].starts_with(['<', '>', '=']) {
+            let op_run: String = rest.chars().take_while(|c| matches!(c, '<' | '>' | '=')).collect();
+            return Err(LexError::new(op_pos, format!("unknown operator: {:?}", op_run)));
+        }
+        self.pos += op.chars().count();
+        let (value, quoted_value) = self.value()?;
+        if col_operator.is_pattern() && !quoted_value {
+            self.check_pattern_end()?;
+        }
+        Ok(ExprToken {
+            col: Spanned { text: col, pos: col_pos },
+            operator: Spanned { text: op.to_string(), pos: op_pos },
+            value,
+            quoted_value,
+            list: None,
+        })
+    }
+    /// Read the operator and the value of an expression whose
+    /// operator is a keyword
+    fn keyword_expr(&mut self, col: Spanned) -> Result<ExprToken, LexError> {
+        let op_pos = self.pos;
+        let operator = if self.at_keyword("in") {
+            self.pos += 2;
+            self.skip_whitespace();
+            if self.peek() == Some('(') {
+                let (value, list) = self.list()?;
+                return Ok(ExprToken {
+                    col,
+                    operator: Spanned { text: "in".to_string(), pos: op_pos },
+                    value,
+                    quoted_value: false,
+                    list: Some(list),
+                });
+            }
+            "in"
+        } else {
+            self.pos += 2;
+            self.skip_whitespace();
+            if self.at_keyword("not") {
+                self.pos += 3;
+                "is not"
+            } else {
+                "is"
+            }
+        };
+        let (value, quoted_value) = self.value()?;
+        Ok(ExprToken {
+            col,
+            operator: Spanned { text: operator.to_string(), pos: op_pos },
+            value,
+            quoted_value,
+            list: None,
+        })
+    }
+    /// Read a parenthesized and comma separated list of values,
+    /// starting at the opening parenthesis
+    fn list(&mut self) -> Result<(Spanned, Vec<ListItem>), LexError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let pos = self.pos;
+            let mut value = String::new();
+            let mut has_value = false;
+            let mut quoted = false;
+            loop {
+                match self.peek() {
+                    None => {
+                        return Err(LexError::new(start, "unclosed '('"));
+                    }
+                    Some(',' | ')') => break,
+                    Some(c @ ('\'' | '"')) => {
+                        value.push_str(&self.quoted(c)?);
+                        quoted = true;
+                    }
+                    Some(c) if c.is_whitespace() => {
+                        let space = self.pos;
+                        self.skip_whitespace();
+                        if !matches!(self.peek(), None | Some(',' | ')')) {
+                            self.pos = space;
+                            self.skip_value_whitespace()?;
+                        }
+                        continue;
+                    }
+                    Some(c) => {
+                        value.push(c);
+                        self.pos += 1;
+                    }
+                }
+                has_value = true;
+            }
+            if !has_value {
+                return Err(LexError::new(pos, "no value"));
+            }
+            items.push(ListItem {
+                value: Spanned { text: value, pos },
+                quoted,
+            });
+            let sep = self.peek();
+            self.pos += 1;
+            if sep == Some(')') {
+                break;
+            }
+        }
+        let text = items
+            .iter()
+            .map(|item| item.value.text.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok((Spanned { text, pos: start }, items))
+    }
+    /// Read a value, made of unquoted and quoted parts, and
+    /// tell whether it was quoted
+    fn value(&mut self) -> Result<(Spanned, bool), LexError> {
+        self.skip_whitespace();
+        let value_pos = self.pos;
+        let mut value = String::new();
+        let mut has_value = false;
+        let mut quoted_value = false;
+        while let Some(c) = self.peek() {
+            match c {
+                '&' | '|' | '(' | ')' => break,
+                '\'' | '"' => {
+                    value.push_str(&self.quoted(c)?);
+                    quoted_value = true;
+                }
+                c if c.is_whitespace() => {
+                    let space = self.pos;
+                    self.skip_whitespace();
+                    if matches!(self.peek(), None | Some('&' | '|' | '(' | ')' | '!')) {
+                        break;
+                    }
+                    self.pos = space;
+                    self.skip_value_whitespace()?;
+                    continue;
+                }
+                c => {
+                    value.push(c);
+                    self.pos += 1;
+                }
+            }
+            has_value = true;
+        }
+        if !has_value {
+            return Err(LexError::new(value_pos, "no value"));
+        }
+        Ok((Spanned { text: value, pos: value_pos }, quoted_value))
+    }
+    /// Check that an unquoted pattern isn't followed by chars which
+    /// are probably part of it, like in `fs ~ a|b` or `mount ~ /x(y)`
+    fn check_pattern_end(&self) -> Result<(), LexError> {
+        let is_part = match self.peek() {
+            Some('(') => true,
+            // a boolean operator must be followed by an expression
+            Some('&' | '|') => {
+                let mut next = self.clone();
+                next.pos += 1;
+                next.skip_whitespace();
+                !matches!(next.peek(), Some('(' | '!')) && next.expr().is_err()
+            }
+            _ => false,
+        };
+        match self.peek() {
+            Some(c) if is_part => Err(LexError::new(
+                self.pos,
+                format!("a pattern containing {:?} must be quoted, eg 'a{}b'", c, c),
+            )),
+            _ => Ok(()),
+        }
+    }
+    /// Read a quoted string, starting at the opening quote
+    fn quoted(&mut self, quote: char) -> Result<String, LexError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            match self.peek() {
+                None => {
+                    return Err(LexError::new(start, "unclosed quote"));
+                }
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some('\\') => {
+                    match self.chars.get(self.pos + 1) {
+                        Some(&c) if c == quote || c == '\\' => {
+                            s.push(c);
+                            self.pos += 2;
+                        }
+                        _ => {
+                            s.push('\\');
+                            self.pos += 1;
+                        }
+                    }
+                }
+                Some(c) => {
+                    s.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod filter_lexing {
+    use super::{*, Token::*};
+
+    fn expr(col: &str, operator: &str, value: &str) -> Token {
+        Expr(ExprToken {
+            col: Spanned { text: col.to_string(), pos: 0 },
+            operator: Spanned { text: operator.to_string(), pos: 0 },
+            value: Spanned { text: value.to_string(), pos: 0 },
+            quoted_value: false,
+            list: None,
+        })
+    }
+
+    fn list_items(input: &str) -> Vec<(String, bool)> {
+        let Expr(e) = &lex(input).unwrap()[0] else {
+            panic!("expected an expression");
+        };
+        e.list
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|item| (item.value.text.clone(), item.quoted))
+            .collect()
+    }
+
+    /// lex, forgetting the positions
+    fn tokens(input: &str) -> Vec<Token> {
+        lex(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| match token {
+                // quoting is checked in quoted_values
+                Expr(e) => expr(&e.col.text, &e.operator.text, &e.value.text),
+                token => token,
+            })
+            .collect()
+    }
+
+    fn error(input: &str) -> (usize, String) {
+        let e = lex(input).unwrap_err();
+        (e.pos, e.message)
+    }
+
+    #[test]
+    fn all_operators() {
+        for op in ["<", "<=", ">", ">=", "<>", "=", "==", "~", "!~", "*=", "!*="] {
+            assert_eq!(tokens(&format!("fs{op}x")), vec![expr("fs", op, "x")]);
+        }
+    }
+
+    #[test]
+    fn existing_syntax() {
+        assert_eq!(tokens("type=xfs"), vec![expr("type", "=", "xfs")]);
+        assert_eq!(tokens("size<32G"), vec![expr("size", "<", "32G")]);
+        assert_eq!(
+            tokens("free<100G | use>65%"),
+            vec![expr("free", "<", "100G"), Or, expr("use", ">", "65%")],
+        );
+        assert_eq!(
+            tokens("(size<35G | remote=false) & type=xfs"),
+            vec![
+                OpenPar,
+                expr("size", "<", "35G"),
+                Or,
+                expr("remote", "=", "false"),
+                ClosePar,
+                And,
+                expr("type", "=", "xfs"),
+            ],
+        );
+        assert_eq!(
+            tokens("!remote=true&!(type==nfs|type==cifs)"),
+            vec![
+                Not,
+                expr("remote", "=", "true"),
+                And,
+                Not,
+                OpenPar,
+                expr("type", "==", "nfs"),
+                Or,
+                expr("type", "==", "cifs"),
+                ClosePar,
+            ],
+        );
+        // spaces are ignored out of quotes
+        assert_eq!(tokens("  type = xfs "), vec![expr("type", "=", "xfs")]);
+        assert_eq!(tokens("size in 1G .. 10G"), vec![expr("size", "in", "1G..10G")]);
+        assert_eq!(
+            tokens("!(!type=xfs | (size<1G))"),
+            vec![
+                Not,
+                OpenPar,
+                Not,
+                expr("type", "=", "xfs"),
+                Or,
+                OpenPar,
+                expr("size", "<", "1G"),
+                ClosePar,
+                ClosePar,
+            ],
+        );
+        assert_eq!(tokens(""), vec![]);
+        // globs and regexes
+        assert_eq!(tokens("fs*=lustre-OST00*"), vec![expr("fs", "*=", "lustre-OST00*")]);
+        assert_eq!(tokens("fs*=*_UUID"), vec![expr("fs", "*=", "*_UUID")]);
+        assert_eq!(tokens("fs*=OST000[!0-3]"), vec![expr("fs", "*=", "OST000[!0-3]")]);
+        assert_eq!(
+            tokens("job !~ ^rsync & !target*=*MDT*"),
+            vec![expr("job", "!~", "^rsync"), And, Not, expr("target", "*=", "*MDT*")],
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(tokens("free < size*5%"), vec![expr("free", "<", "size*5%")]);
+        assert_eq!(
+            tokens("inodes_free < 1M & used > free"),
+            vec![expr("inodes_free", "<", "1M"), And, expr("used", ">", "free")],
+        );
+        assert_eq!(
+            tokens("iuse_percent > use_percent + 20%"),
+            vec![expr("iuse_percent", ">", "use_percent+20%")],
+        );
+        assert_eq!(tokens("size * 5% > free"), vec![expr("size*5%", ">", "free")]);
+        assert_eq!(tokens("size-used>=1G"), vec![expr("size-used", ">=", "1G")]);
+    }
+
+    #[test]
+    fn quoted_values() {
+        assert_eq!(tokens("label='My Disk'"), vec![expr("label", "=", "My Disk")]);
+        assert_eq!(tokens(r#"label=="My Disk""#), vec![expr("label", "==", "My Disk")]);
+        assert_eq!(
+            tokens("mount='/data (old) & new' | label=x"),
+            vec![expr("mount", "=", "/data (old) & new"), Or, expr("label", "=", "x")],
+        );
+        assert_eq!(tokens(r"label='it\'s'"), vec![expr("label", "=", "it's")]);
+        assert_eq!(tokens(r#"label="say \"hi\"""#), vec![expr("label", "=", "say \"hi\"")]);
+        assert_eq!(tokens(r"label='a\\b'"), vec![expr("label", "=", r"a\b")]);
+        // other backslashes are kept for regexes
+        assert_eq!(tokens(r"mount~'^/a\.b/(x|y)$'"), vec![expr("mount", "~", r"^/a\.b/(x|y)$")]);
+        // quoted and unquoted parts are joined
+        assert_eq!(tokens("mount=/mnt/'my disk'/x"), vec![expr("mount", "=", "/mnt/my disk/x")]);
+        let Expr(e) = &lex("label='x'").unwrap()[0] else {
+            panic!("expected an expression");
+        };
+        assert!(e.quoted_value);
+        // a quoted value may start with an operator char or be empty
+        assert_eq!(tokens("label='<none>'"), vec![expr("label", "=", "<none>")]);
+        assert_eq!(tokens("label==''"), vec![expr("label", "==", "")]);
+    }
+
+    #[test]
+    fn keyword_operators() {
+        assert_eq!(tokens("type in (xfs, ext4, lustre)"), vec![expr("type", "in", "xfs, ext4, lustre")]);
+        assert_eq!(
+            list_items("type in (xfs, 'my fs',ext4)"),
+            vec![("xfs".to_string(), false), ("my fs".to_string(), true), ("ext4".to_string(), false)],
+        );
+        assert_eq!(tokens("type IN(xfs)"), vec![expr("type", "in", "xfs")]);
+        assert_eq!(tokens("comp_idx in 0..15"), vec![expr("comp_idx", "in", "0..15")]);
+        assert_eq!(
+            tokens("label is null | pool is not null"),
+            vec![expr("label", "is", "null"), Or, expr("pool", "is not", "null")],
+        );
+        assert_eq!(
+            tokens("!(type in (nfs,cifs)) & size in 1G .. 10G"),
+            vec![
+                Not,
+                OpenPar,
+                expr("type", "in", "nfs, cifs"),
+                ClosePar,
+                And,
+                expr("size", "in", "1G..10G"),
+            ],
+        );
+        // a keyword must be separated from the column
+        assert_eq!(tokens("size*2 is null"), vec![expr("size*2", "is", "null")]);
+        assert_eq!(error("type in (xfs, ext4"), (8, "unclosed '('".to_string()));
+        assert_eq!(error("type in (xfs,,ext4)"), (13, "no value".to_string()));
+        assert_eq!(error("label is"), (8, "no value".to_string()));
+    }
+
+    #[test]
+    fn positions() {
+        let tokens = lex("type=xfs | mount ~ '/a b'").unwrap();
+        let Expr(e) = &tokens[2] else {
+            panic!("expected an expression");
+        };
+        assert_eq!(e.col.pos, 11);
+        assert_eq!(e.operator.pos, 17);
+        assert_eq!(e.value.pos, 19);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error("label='abc"), (6, "unclosed quote".to_string()));
+        assert_eq!(error("type=xfs | size"), (15, "expected an operator; expressions look like <column><operator><value>".to_string()));
+        assert_eq!(error("size=>3"), (4, "unknown operator: \"=>\"".to_string()));
+        assert_eq!(error("size!3"), (4, "unknown operator: \"!\"".to_string()));
+        assert_eq!(error("size< "), (6, "no value".to_string()));
+        assert_eq!(error("(type=xfs"), (0, "unclosed '('".to_string()));
+        assert_eq!(error("type=xfs)"), (8, "unexpected ')'".to_string()));
+        assert_eq!(error("'type'=xfs"), (0, "expected a column name".to_string()));
+    }
+
+    #[test]
+    fn misplaced_boolean_operators() {
+        assert_eq!(error("type=xfs &"), (10, "expected an expression".to_string()));
+        assert_eq!(error("& type=xfs"), (0, "expected an expression before '&'".to_string()));
+        assert_eq!(error("type=xfs & | size<1G"), (11, "expected an expression before '|'".to_string()));
+        assert_eq!(error("!"), (1, "expected an expression".to_string()));
+        assert_eq!(error("()"), (1, "expected an expression before ')'".to_string()));
+        assert_eq!(error("(type=xfs |)"), (11, "expected an expression before ')'".to_string()));
+        assert_eq!(error("(type=xfs) size<1G"), (11, "expected '&' or '|' between expressions".to_string()));
+        assert_eq!(error("type=xfs !size<1G"), (9, "expected '&' or '|' between expressions".to_string()));
+        assert_eq!(error("type=xfs (size<1G)"), (9, "expected '&' or '|' between expressions".to_string()));
+    }
+
+    #[test]
+    fn spaces_in_unquoted_values() {
+        let message = "unexpected space in a value; quote the value or separate expressions with '&' or '|'";
+        assert_eq!(error("size > 1 G"), (9, message.to_string()));
+        assert_eq!(error("type=xfs type=ext4"), (9, message.to_string()));
+        assert_eq!(error("type in (xfs, my fs)"), (17, message.to_string()));
+        assert_eq!(error("label='My' Disk"), (11, message.to_string()));
+        // spaces around arithmetic and range operators are fine
+        assert_eq!(tokens("used > size - 10G"), vec![expr("used", ">", "size-10G")]);
+        assert_eq!(tokens("type in ( xfs , ext4 )"), vec![expr("type", "in", "xfs, ext4")]);
+    }
+
+    #[test]
+    fn unquoted_patterns() {
+        assert_eq!(error("fs ~ a|b"), (6, "a pattern containing '|' must be quoted, eg 'a|b'".to_string()));
+        assert_eq!(error("fs ~ a | b"), (7, "a pattern containing '|' must be quoted, eg 'a|b'".to_string()));
+        assert_eq!(error("mount ~ /x(y)"), (10, "a pattern containing '(' must be quoted, eg 'a(b'".to_string()));
+        assert_eq!(error("mount *= /x&y"), (11, "a pattern containing '&' must be quoted, eg 'a&b'".to_string()));
+        // boolean operators after a pattern are still possible
+        assert_eq!(
+            tokens("fs~a|type=xfs"),
+            vec![expr("fs", "~", "a"), Token::Or, expr("type", "=", "xfs")],
+        );
+        assert_eq!(
+            tokens("(fs ~ a) & !(type=xfs)"),
+            vec![
+                Token::OpenPar,
+                expr("fs", "~", "a"),
+                Token::ClosePar,
+                Token::And,
+                Token::Not,
+                Token::OpenPar,
+                expr("type", "=", "xfs"),
+                Token::ClosePar,
+            ],
+        );
+        assert_eq!(tokens("fs ~ 'a|b'"), vec![expr("fs", "~", "a|b")]);
+        assert_eq!(tokens("mount ~ '/x(y)'"), vec![expr("mount", "~", "/x(y)")]);
+    }
+}
@@ -0,0 +1,243 @@
+use {
+    crate::stamp::Stamp,
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde_json::{Map, Value},
+    std::{
+        error,
+        fmt,
+    },
+};
+
+/// The output format
+#[derive(ValueEnum, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+    Yaml,
+    Toml,
+    /// newline-delimited JSON: one compact record per line
+    Ndjson,
+    /// InfluxDB line protocol
+    Influx,
+    /// Graphite plaintext protocol, with tags
+    Graphite,
+}
+
+impl Format {
+    /// Whether it's a format of time-series databases, which
+    /// only makes sense for the filesystems
+    pub fn is_metrics(self) -> bool {
+        matches!(self, Self::Influx | Self::Graphite)
+    }
+    /// Serialize the structure of the JSON output in this format,
+    /// ending with a newline.
+    ///
+    /// As a TOML document must be a table, a value which isn't
+    /// an object is put under `root_key`.
+    ///
+    /// In NDJSON, each item of an array is a record, stamped with
+    /// the host and the time.
+    pub fn serialize(self, root_key: &str, value: Value) -> Result<String, SerializeError> {
+        let err = |e: &dyn fmt::Display| SerializeError::new(self, e);
+        let s = match self {
+            Self::Yaml => {
+                let mut yaml = String::new();
+                write_yaml_node(&mut yaml, &value, 0);
+                yaml
+            }
+            Self::Toml => {
+                let value = match without_nulls(value) {
+                    Value::Object(map) => Value::Object(map),
+                    value => {
+                        let mut map = Map::new();
+                        map.insert(root_key.to_string(), value);
+                        Value::Object(map)
+                    }
+                };
+                // TOML integers are signed, so a too big number fails
+                toml::to_string(&value).map_err(|e| err(&e))?
+            }
+            Self::Ndjson => {
+                let stamp = Stamp::now();
+                let records = match value {
+                    Value::Array(records) => records,
+                    record => vec![record],
+                };
+                records
+                    .into_iter()
+                    .map(|record| format!("{}\n", stamp.apply(record)))
+                    .collect()
+            }
+            // the table, CSV, and metrics formats aren't serializations
+            // of the JSON structure, they're handled by their own modules
+            _ => format!("{}\n", serde_json::to_string_pretty(&value).map_err(|e| err(&e))?),
+        };
+        Ok(s)
+    }
+}
+
+/// The reason why a value can't be written in a format
+#[derive(Debug)]
+pub struct SerializeError {
+    format: Format,
+    reason: String,
+}
+impl SerializeError {
+    pub fn new<E: ToString + ?Sized>(format: Format, reason: &E) -> Self {
+        Self {
+            format,
+            reason: reason.to_string(),
+        }
+    }
+}
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Can't write the {:?} output: {}", self.format, self.reason)
+    }
+}
+impl error::Error for SerializeError {}
+
+/// Write a YAML node in block style, the caller having already written
+/// what comes before on its first line (a `- ` or nothing).
+///
+/// Following lines are indented by `indent` spaces.
+fn write_yaml_node(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Array(items) if !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(&" ".repeat(indent));
+                }
+                out.push_str("- ");
+                write_yaml_node(out, item, indent + 2);
+            }
+        }
+        Value::Object(map) if !map.is_empty() => {
+            for (i, (key, value)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(&" ".repeat(indent));
+                }
+                out.push_str(&yaml_string(key));
+                out.push(':');
+                match value {
+                    Value::Object(map) if !map.is_empty() => {
+                        out.push('\n');
+                        out.push_str(&" ".repeat(indent + 2));
+                        write_yaml_node(out, value, indent + 2);
+                    }
+                    // sequences aren't indented in mappings
+                    Value::Array(items) if !items.is_empty() => {
+                        out.push('\n');
+                        out.push_str(&" ".repeat(indent));
+                        write_yaml_node(out, value, indent);
+                    }
+                    _ => {
+                        out.push(' ');
+                        write_yaml_node(out, value, indent);
+                    }
+                }
+            }
+        }
+        Value::Array(_) => out.push_str("[]\n"),
+        Value::Object(_) => out.push_str("{}\n"),
+        Value::String(s) => {
+            out.push_str(&yaml_string(s));
+            out.push('\n');
+        }
+        // null, booleans and numbers are written as in JSON
+        value => {
+            out.push_str(&value.to_string());
+            out.push('\n');
+        }
+    }
+}
+
+/// Write a string as a plain YAML scalar when it can't be read
+/// as something else, and double quoted otherwise (a JSON string
+/// being a valid YAML double quoted scalar)
+fn yaml_string(s: &str) -> String {
+    let lowercase = s.to_lowercase();
+    let plain = !s.is_empty()
+        && !s.starts_with([
+            ' ', '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%',
+            '@', '`', '.', '+', '~',
+        ])
+        && !s.ends_with([' ', ':'])
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.chars().any(char::is_control)
+        && !matches!(
+            lowercase.as_str(),
+            "null" | "true" | "false" | "yes" | "no" | "on" | "off" | "y" | "n"
+        )
+        && !lowercase.starts_with("0x")
+        && !lowercase.starts_with("0o")
+        && s.replace('_', "").parse::<f64>().is_err();
+    if plain {
+        s.to_string()
+    } else {
+        Value::String(s.to_string()).to_string()
+    }
+}
+
+/// Remove the null values, which TOML can't represent
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, without_nulls(v)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(
+            values.into_iter()
+                .filter(|v| !v.is_null())
+                .map(without_nulls)
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[test]
+fn test_serialize() {
+    use serde_json::json;
+    let value = json!([
+        { "fs": "/dev/sda1", "label": null, "stats": { "size": 1000, "inodes": null } },
+        { "fs": "/dev/sdb1", "label": "data", "stats": null },
+    ]);
+    assert_eq!(
+        Format::Yaml.serialize("mounts", value.clone()).unwrap(),
+        "- fs: /dev/sda1\n  label: null\n  stats:\n    inodes: null\n    size: 1000\n- fs: /dev/sdb1\n  label: data\n  stats: null\n",
+    );
+    assert_eq!(
+        Format::Yaml.serialize("mounts", json!({ "lists": [[1, 2], []], "map": {}, "share": 0.5 })).unwrap(),
+        "lists:\n- - 1\n  - 2\n- []\nmap: {}\nshare: 0.5\n",
+    );
+    // strings which would be read as something else are quoted
+    assert_eq!(
+        Format::Yaml.serialize("mounts", json!(["no", "12", "1_000", "", "a: b", "-x", "line\nbreak", "OST0001"])).unwrap(),
+        "- \"no\"\n- \"12\"\n- \"1_000\"\n- \"\"\n- \"a: b\"\n- \"-x\"\n- \"line\\nbreak\"\n- OST0001\n",
+    );
+    let toml = Format::Toml.serialize("mounts", value).unwrap();
+    let parsed: toml::Value = toml.parse().unwrap();
+    let mounts = parsed["mounts"].as_array().unwrap();
+    assert_eq!(mounts.len(), 2);
+    assert_eq!(mounts[0]["stats"]["size"].as_integer(), Some(1000));
+    assert!(mounts[0].get("label").is_none());
+    assert_eq!(mounts[1]["label"].as_str(), Some("data"));
+    let ndjson = Format::Ndjson.serialize("mounts", json!([{ "fs": "a" }, { "fs": "b" }])).unwrap();
+    let lines: Vec<&str> = ndjson.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let record: Value = serde_json::from_str(lines[1]).unwrap();
+    assert_eq!(record["fs"], "b");
+    assert!(record["host"].is_string());
+    assert!(record["timestamp"].as_str().unwrap().ends_with('Z'));
+    // TOML has no integer above i64::MAX
+    let e = Format::Toml.serialize("mounts", json!({ "size": u64::MAX })).unwrap_err();
+    assert!(e.to_string().starts_with("Can't write the Toml output"));
+}
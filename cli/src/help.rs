@@ -39,7 +39,8 @@ static EXAMPLES: &[Example] = &[
     Example::new(
         "Display inodes information with custom columns",
         "dysk -c +inodes",
-        "Add dedicated inode columns alongside byte columns"
+        "Add the `inodes` column, a graphical view of the inodes use, alongside byte columns. \
+        `dysk -c +inodes_all` adds all the inode columns"
     ),
     Example::new(
         "Add columns of your choice",
@@ -68,16 +69,67 @@ static EXAMPLES: &[Example] = &[
         "dysk -f '(type=xfs & remote=no) | size > 5T'",
         ""
     ),
+    Example::new(
+        "Use a named view",
+        "dysk @full-disks",
+        "Views bundle columns, filter, sort, and format. \
+        Define yours in the configuration, see https://dystroy.org/dysk/config#views\n"
+    ),
     Example::new(
         "Export as JSON",
         "dysk -j",
         ""
     ),
+    Example::new(
+        "Export as YAML",
+        "dysk --format yaml",
+        "Other formats are `table`, `json`, `csv`, `toml`, `ndjson`, `influx`, and `graphite`."
+    ),
+    Example::new(
+        "Append a JSON record per filesystem every minute",
+        "dysk --format ndjson --interval 60",
+        ""
+    ),
+    Example::new(
+        "Feed a time-series database",
+        "dysk --format influx",
+        "Use `--format graphite` for the Graphite plaintext protocol."
+    ),
     Example::new(
         "Sort by free size",
         "dysk -s free",
         "Add `-desc` to the column name to sort in reverse."
     ),
+    Example::new(
+        "Sort by type, then by usage",
+        "dysk -s 'type,use-desc'",
+        "Later keys only order the rows which are equal on the previous ones"
+    ),
+    Example::new(
+        "Find the jobs writing the most on the local OSTs",
+        "dysk --jobs -f 'target=OST' -s write",
+        "Needs jobstats to be enabled. See the job columns with `dysk --jobs --list-cols`"
+    ),
+    Example::new(
+        "Sum the space of the OSTs",
+        "dysk -f 'comp_type=OST' --total",
+        "With `--json`, the output becomes an object with `mounts` and `totals`"
+    ),
+    Example::new(
+        "Check the recovery of the targets of an OSS or MDS",
+        "dysk --server",
+        ""
+    ),
+    Example::new(
+        "Find the stale changelog users of the local MDTs",
+        "dysk --changelogs",
+        "Records not cleared by a changelog user are kept on the MDT"
+    ),
+    Example::new(
+        "Check whether a Lustre directory is archived or released",
+        "dysk -c fs+hsm_state+hsm_archive_id /lustre/project/data",
+        "Without path, the HSM state is the one of the mount point"
+    ),
 ];
 
 pub fn print(ascii: bool) {
@@ -0,0 +1,266 @@
+use {
+    crate::{
+        lustre_proc::LustreProc,
+        lustre_target::{LustreTargetName, TargetKind},
+    },
+    std::{
+        fs::OpenOptions,
+        io,
+        mem,
+        os::unix::{
+            fs::OpenOptionsExt,
+            io::AsRawFd,
+        },
+        path::{Path, PathBuf},
+    },
+};
+
+// HSM flags of a file, as defined in lustre_user.h
+pub const HS_EXISTS: u32 = 0x01;
+pub const HS_DIRTY: u32 = 0x02;
+pub const HS_RELEASED: u32 = 0x04;
+pub const HS_ARCHIVED: u32 = 0x08;
+pub const HS_LOST: u32 = 0x40;
+
+static FLAG_NAMES: &[(u32, &str)] = &[
+    (HS_EXISTS, "exists"),
+    (HS_ARCHIVED, "archived"),
+    (HS_RELEASED, "released"),
+    (HS_DIRTY, "dirty"),
+    (HS_LOST, "lost"),
+];
+
+/// The `struct hsm_user_state` filled by the LL_IOC_HSM_STATE_GET ioctl.
+///
+/// Its size is part of the ioctl number, so the layout must match the
+/// C struct, including the length of the extended info (whose variable
+/// part we don't read).
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)] // the in progress fields are only written by the kernel
+struct HsmUserState {
+    states: u32,
+    archive_id: u32,
+    in_progress_state: u32,
+    in_progress_action: u32,
+    in_progress_offset: u64,
+    in_progress_length: u64,
+    extended_info_len: u32,
+}
+
+const _: () = assert!(mem::size_of::<HsmUserState>() == 40);
+
+/// `_IOR('f', 211, struct hsm_user_state)`
+const LL_IOC_HSM_STATE_GET: u32 = (2 << 30)
+    | ((mem::size_of::<HsmUserState>() as u32) << 16)
+    | ((b'f' as u32) << 8)
+    | 211;
+
+/// The HSM state of a file or directory, what `lfs hsm_state` shows
+#[derive(Debug, Clone, PartialEq)]
+pub struct HsmState {
+    pub path: PathBuf,
+    /// a combination of the HS_* flags
+    pub flags: u32,
+    /// the archive the file is in, 0 when not archived
+    pub archive_id: u32,
+}
+
+impl HsmState {
+    pub fn exists(&self) -> bool {
+        self.flags & HS_EXISTS != 0
+    }
+    pub fn archived(&self) -> bool {
+        self.flags & HS_ARCHIVED != 0
+    }
+    pub fn released(&self) -> bool {
+        self.flags & HS_RELEASED != 0
+    }
+    pub fn dirty(&self) -> bool {
+        self.flags & HS_DIRTY != 0
+    }
+    pub fn lost(&self) -> bool {
+        self.flags & HS_LOST != 0
+    }
+    /// The set flags, comma separated, eg "exists,archived,released",
+    /// or "none"
+    pub fn summary(&self) -> String {
+        let names: Vec<&str> = FLAG_NAMES
+            .iter()
+            .filter(|(flag, _)| self.flags & flag != 0)
+            .map(|(_, name)| *name)
+            .collect();
+        if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join(",")
+        }
+    }
+}
+
+/// Ask Lustre for the HSM state of a file or directory.
+///
+/// Fails (with ENOTTY) when the path isn't on a Lustre filesystem.
+pub fn read_hsm_state(path: &Path) -> io::Result<HsmState> {
+    // like llapi_hsm_state_get, we don't want to trigger a restore
+    let file = OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK | libc::O_NOFOLLOW)
+        .open(path)?;
+    let mut hus = HsmUserState::default();
+    // SAFETY: the kernel writes at most size_of::<HsmUserState>() bytes,
+    // as encoded in the request
+    let res = unsafe {
+        libc::ioctl(
+            file.as_raw_fd(),
+            LL_IOC_HSM_STATE_GET as _,
+            &mut hus as *mut HsmUserState,
+        )
+    };
+    if res < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(HsmState {
+        path: path.to_path_buf(),
+        flags: hus.states,
+        archive_id: hus.archive_id,
+    })
+}
+
+/// The state of the HSM coordinator of a filesystem and the
+/// number of actions it handles
+#[derive(Debug, Clone, PartialEq)]
+pub struct HsmCoordinator {
+    /// eg "enabled", "disabled", "shutdown"
+    pub state: String,
+    /// actions waiting for a copytool
+    pub pending_actions: usize,
+    /// actions being done by a copytool
+    pub active_actions: usize,
+}
+
+/// Read the coordinator state of a filesystem.
+///
+/// The coordinator runs on the MDTs, so it's only visible on a node
+/// serving a MDT of the filesystem: None is returned elsewhere.
+/// With several MDTs, the state is the one of the first MDT and the
+/// actions of all MDTs are counted.
+pub fn read_hsm_coordinator(proc: &LustreProc, fsname: &str) -> io::Result<Option<HsmCoordinator>> {
+    let mut coordinator: Option<HsmCoordinator> = None;
+    for target in proc.targets("mdt")? {
+        let is_fs_mdt = target
+            .parse::<LustreTargetName>()
+            .is_ok_and(|name| name.fsname == fsname && name.kind == TargetKind::Mdt);
+        if !is_fs_mdt {
+            continue;
+        }
+        let Some(state) = proc.read_param("mdt", &target, "hsm_control")? else {
+            continue;
+        };
+        let (pending, active) = proc
+            .read_param("mdt", &target, "hsm/actions")?
+            .map_or((0, 0), |content| count_hsm_actions(&content));
+        match &mut coordinator {
+            Some(coordinator) => {
+                coordinator.pending_actions += pending;
+                coordinator.active_actions += active;
+            }
+            None => {
+                coordinator = Some(HsmCoordinator {
+                    state: state.trim().to_string(),
+                    pending_actions: pending,
+                    active_actions: active,
+                });
+            }
+        }
+    }
+    Ok(coordinator)
+}
+
+/// Count the pending and active actions in the content of a
+/// hsm/actions file, whose lines look like
+///
+/// ```text
+/// lrh=[type=10680000 len=136 idx=1/3] fid=[0x200000400:0x1:0x0] dfid=[0x200000400:0x1:0x0] compound/cookie=0x0/0x5d5a action=ARCHIVE archive#=1 flags=0x0 extent=0x0-0xffffffffffffffff gid=0x0 datalen=0 status=WAITING data=[]
+/// ```
+pub fn count_hsm_actions(content: &str) -> (usize, usize) {
+    let mut pending = 0;
+    let mut active = 0;
+    for line in content.lines() {
+        let status = line
+            .split_whitespace()
+            .find_map(|token| token.strip_prefix("status="));
+        match status {
+            Some("WAITING") => pending += 1,
+            Some("STARTED") => active += 1,
+            _ => {}
+        }
+    }
+    (pending, active)
+}
+
+#[test]
+fn test_hsm_state_get_ioctl_number() {
+    // LL_IOC_HSM_STATE_GET as computed from lustre_user.h
+    assert_eq!(LL_IOC_HSM_STATE_GET, 0x802866d3);
+}
+
+#[test]
+fn test_hsm_state_summary() {
+    let state = |flags| HsmState {
+        path: PathBuf::from("/lustre/data"),
+        flags,
+        archive_id: 1,
+    };
+    assert_eq!(state(0).summary(), "none");
+    assert_eq!(state(HS_EXISTS).summary(), "exists");
+    let released = state(HS_EXISTS | HS_ARCHIVED | HS_RELEASED);
+    assert_eq!(released.summary(), "exists,archived,released");
+    assert!(released.released());
+    assert!(!released.dirty());
+    assert_eq!(state(HS_EXISTS | HS_DIRTY | HS_LOST | 0x10).summary(), "exists,dirty,lost");
+}
+
+#[test]
+fn test_count_hsm_actions() {
+    let content = "\
+lrh=[type=10680000 len=136 idx=1/3] fid=[0x200000400:0x1:0x0] action=ARCHIVE archive#=1 status=WAITING data=[]
+lrh=[type=10680000 len=136 idx=1/4] fid=[0x200000400:0x2:0x0] action=RESTORE archive#=1 status=STARTED data=[]
+lrh=[type=10680000 len=136 idx=1/5] fid=[0x200000400:0x3:0x0] action=ARCHIVE archive#=1 status=WAITING data=[]
+lrh=[type=10680000 len=136 idx=1/6] fid=[0x200000400:0x4:0x0] action=ARCHIVE archive#=1 status=SUCCEED data=[]
+";
+    assert_eq!(count_hsm_actions(content), (2, 1));
+    assert_eq!(count_hsm_actions(""), (0, 0));
+}
+
+#[test]
+fn test_read_hsm_coordinator() {
+    use crate::lustre_proc::TestTree;
+    let tree = TestTree::new("hsm");
+    tree.write("mdt/scratch-MDT0000/hsm_control", "enabled\n");
+    tree.write("mdt/scratch-MDT0000/hsm/actions", "action=ARCHIVE status=WAITING\naction=RESTORE status=STARTED\n");
+    tree.write("mdt/scratch-MDT0001/hsm_control", "enabled\n");
+    tree.write("mdt/scratch-MDT0001/hsm/actions", "action=ARCHIVE status=WAITING\n");
+    tree.write("mdt/home-MDT0000/hsm_control", "disabled\n");
+    let proc = tree.proc();
+    let scratch = read_hsm_coordinator(&proc, "scratch").unwrap();
+    let home = read_hsm_coordinator(&proc, "home").unwrap();
+    let other = read_hsm_coordinator(&proc, "other").unwrap();
+    assert_eq!(
+        scratch,
+        Some(HsmCoordinator {
+            state: "enabled".to_string(),
+            pending_actions: 2,
+            active_actions: 1,
+        }),
+    );
+    assert_eq!(
+        home,
+        Some(HsmCoordinator {
+            state: "disabled".to_string(),
+            pending_actions: 0,
+            active_actions: 0,
+        }),
+    );
+    assert_eq!(other, None);
+}
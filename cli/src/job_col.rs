@@ -0,0 +1,132 @@
+use {
+    crate::{
+        col::{ParseColError, TableCol},
+        col_value::ColValue,
+        jobstats::JobStat,
+        num_expr::NumKind,
+        order::Order,
+    },
+    std::{
+        fmt,
+        str::FromStr,
+    },
+    termimad::minimad::Alignment,
+};
+
+/// A column of the jobs view (`dysk --jobs`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobCol {
+    JobId,
+    Target,
+    ReadBytes,
+    WriteBytes,
+    MetadataOps,
+}
+
+pub static ALL_JOB_COLS: &[JobCol] = &[
+    JobCol::JobId,
+    JobCol::Target,
+    JobCol::ReadBytes,
+    JobCol::WriteBytes,
+    JobCol::MetadataOps,
+];
+
+impl JobCol {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::JobId => "job",
+            Self::Target => "target",
+            Self::ReadBytes => "read",
+            Self::WriteBytes => "write",
+            Self::MetadataOps => "ops",
+        }
+    }
+    pub fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Self::JobId => &["job_id", "jobid"],
+            Self::Target => &["tgt"],
+            Self::ReadBytes => &["read_bytes"],
+            Self::WriteBytes => &["write_bytes"],
+            Self::MetadataOps => &["md_ops", "metadata_ops"],
+        }
+    }
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::JobId => "job id",
+            Self::Target => "target",
+            Self::ReadBytes => "read",
+            Self::WriteBytes => "written",
+            Self::MetadataOps => "md ops",
+        }
+    }
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::JobId => "job identifier, as set by the jobid_var of the clients",
+            Self::Target => "MDT or OST the activity was measured on",
+            Self::ReadBytes => "bytes read by the job",
+            Self::WriteBytes => "bytes written by the job",
+            Self::MetadataOps => "number of metadata operations of the job",
+        }
+    }
+    pub fn content_align(self) -> Alignment {
+        match self {
+            Self::JobId => Alignment::Left,
+            Self::Target => Alignment::Left,
+            _ => Alignment::Right,
+        }
+    }
+}
+
+impl fmt::Display for JobCol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.title())
+    }
+}
+
+impl FromStr for JobCol {
+    type Err = ParseColError;
+    fn from_str(s: &str) -> Result<Self, ParseColError> {
+        ALL_JOB_COLS
+            .iter()
+            .find(|col| col.name() == s || col.aliases().contains(&s))
+            .copied()
+            .ok_or_else(|| ParseColError::new(s))
+    }
+}
+
+impl TableCol for JobCol {
+    type Row = JobStat;
+    fn default_sort_col() -> Self {
+        Self::WriteBytes
+    }
+    fn default_sort_order(self) -> Order {
+        match self {
+            Self::JobId => Order::Asc,
+            Self::Target => Order::Asc,
+            Self::ReadBytes => Order::Desc,
+            Self::WriteBytes => Order::Desc,
+            Self::MetadataOps => Order::Desc,
+        }
+    }
+    fn is_text(self) -> bool {
+        matches!(self, Self::JobId | Self::Target)
+    }
+    fn num_kind(self) -> Option<NumKind> {
+        match self {
+            Self::JobId | Self::Target => None,
+            _ => Some(NumKind::Amount),
+        }
+    }
+    fn value(self, job: &JobStat) -> ColValue {
+        match self {
+            Self::JobId => ColValue::Text(job.job_id.clone()),
+            Self::Target => job
+                .target
+                .parse()
+                .map_or_else(|_| ColValue::Text(job.target.clone()), ColValue::Target),
+            Self::ReadBytes => ColValue::Size(job.read_bytes),
+            Self::WriteBytes => ColValue::Size(job.write_bytes),
+            Self::MetadataOps => ColValue::Int(job.metadata_ops),
+        }
+    }
+}
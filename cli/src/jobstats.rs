@@ -0,0 +1,175 @@
+use {
+    crate::lustre_proc::LustreProc,
+    std::io,
+};
+
+/// The kinds of targets whose directory may hold a job_stats file
+static JOB_STATS_KINDS: &[&str] = &["mdt", "obdfilter"];
+
+/// Keys of a job_stats entry which aren't counters
+static NON_COUNTER_KEYS: &[&str] = &["job_id", "snapshot_time", "start_time", "elapsed_time"];
+
+/// Keys of a job_stats entry which aren't metadata operations
+static IO_KEYS: &[&str] = &["read_bytes", "write_bytes", "read", "write"];
+
+/// The activity of a job on a Lustre target, as reported
+/// by the job_stats of the target
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobStat {
+    pub job_id: String,
+    /// name of the target, eg "lustre-OST0001"
+    pub target: String,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    /// sum of the samples of all other operations
+    pub metadata_ops: u64,
+}
+
+impl JobStat {
+    fn new<J: Into<String>, T: Into<String>>(job_id: J, target: T) -> Self {
+        Self {
+            job_id: job_id.into(),
+            target: target.into(),
+            read_bytes: 0,
+            write_bytes: 0,
+            metadata_ops: 0,
+        }
+    }
+}
+
+/// Read the job stats of all the local MDT and OST targets.
+///
+/// The returned list is empty when there's no local target or
+/// when jobstats aren't enabled.
+pub fn read_job_stats(proc: &LustreProc) -> io::Result<Vec<JobStat>> {
+    let mut jobs = Vec::new();
+    for kind in JOB_STATS_KINDS {
+        for target in proc.targets(kind)? {
+            if let Some(content) = proc.read_param(kind, &target, "job_stats")? {
+                jobs.append(&mut parse_job_stats(&target, &content));
+            }
+        }
+    }
+    Ok(jobs)
+}
+
+/// Parse the content of a job_stats file, which looks like
+///
+/// ```yaml
+/// job_stats:
+/// - job_id:          dd.1000
+///   snapshot_time:   1700000000
+///   read_bytes:      { samples: 3, unit: bytes, min: 4096, max: 1048576, sum: 1056768 }
+///   write_bytes:     { samples: 0, unit: bytes, min: 0, max: 0, sum: 0 }
+///   punch:           { samples: 1, unit: reqs }
+/// ```
+pub fn parse_job_stats(target: &str, content: &str) -> Vec<JobStat> {
+    let mut jobs: Vec<JobStat> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        let line = match line.strip_prefix('-') {
+            Some(entry_start) => {
+                let Some(job_id) = entry_start.trim().strip_prefix("job_id:") else {
+                    continue;
+                };
+                let job_id = job_id.trim().trim_matches('"');
+                jobs.push(JobStat::new(job_id, target));
+                continue;
+            }
+            None => line,
+        };
+        let Some(job) = jobs.last_mut() else {
+            continue; // the "job_stats:" header
+        };
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim();
+        if NON_COUNTER_KEYS.contains(&key) {
+            continue;
+        }
+        let Some(fields) = value.trim().strip_prefix('{').and_then(|v| v.strip_suffix('}')) else {
+            continue;
+        };
+        match key {
+            "read_bytes" => {
+                job.read_bytes += counter_field(fields, "sum");
+            }
+            "write_bytes" => {
+                job.write_bytes += counter_field(fields, "sum");
+            }
+            _ if IO_KEYS.contains(&key) => {}
+            _ => {
+                job.metadata_ops += counter_field(fields, "samples");
+            }
+        }
+    }
+    jobs
+}
+
+/// Get a numeric field in the content of a counter, eg
+/// "samples: 3, unit: bytes, min: 4096, max: 1048576, sum: 1056768"
+fn counter_field(fields: &str, name: &str) -> u64 {
+    fields
+        .split(',')
+        .filter_map(|field| field.split_once(':'))
+        .find(|(key, _)| key.trim() == name)
+        .and_then(|(_, value)| value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_parse_ost_job_stats() {
+    let content = r#"job_stats:
+- job_id:          dd.1000
+  snapshot_time:   1700000000
+  read_bytes:      { samples:           3, unit: bytes, min:    4096, max: 1048576, sum:         1056768 }
+  write_bytes:     { samples:           2, unit: bytes, min: 1048576, max: 1048576, sum:         2097152 }
+  read:            { samples:           3, unit: usecs, min:      12, max:     870, sum:            1002 }
+  write:           { samples:           2, unit: usecs, min:     300, max:     310, sum:             610 }
+  getattr:         { samples:           0, unit:  reqs }
+  setattr:         { samples:           1, unit:  reqs }
+  punch:           { samples:           4, unit:  reqs }
+- job_id:          "rsync.0"
+  snapshot_time:   1700000042
+  read_bytes:      { samples:           0, unit: bytes, min:       0, max:       0, sum:               0 }
+  write_bytes:     { samples:           1, unit: bytes, min:    4096, max:    4096, sum:            4096 }
+  sync:            { samples:           2, unit:  reqs }
+"#;
+    assert_eq!(
+        parse_job_stats("lustre-OST0001", content),
+        vec![
+            JobStat {
+                job_id: "dd.1000".to_string(),
+                target: "lustre-OST0001".to_string(),
+                read_bytes: 1056768,
+                write_bytes: 2097152,
+                metadata_ops: 5,
+            },
+            JobStat {
+                job_id: "rsync.0".to_string(),
+                target: "lustre-OST0001".to_string(),
+                read_bytes: 0,
+                write_bytes: 4096,
+                metadata_ops: 2,
+            },
+        ],
+    );
+}
+
+#[test]
+fn test_parse_mdt_job_stats() {
+    let content = r#"job_stats:
+- job_id:          bash.0
+  snapshot_time:   1700000000
+  open:            { samples:          12, unit: usecs, min:       5, max:      40, sum:             190, sumsq:        4200 }
+  close:           { samples:          12, unit: usecs, min:       2, max:      10, sum:              50, sumsq:         300 }
+  mkdir:           { samples:           1, unit: usecs, min:      80, max:      80, sum:              80, sumsq:        6400 }
+  getattr:         { samples:           7, unit: usecs, min:       1, max:       9, sum:              20, sumsq:          90 }
+"#;
+    let jobs = parse_job_stats("lustre-MDT0000", content);
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].metadata_ops, 32);
+    assert_eq!(jobs[0].read_bytes, 0);
+    assert!(parse_job_stats("lustre-MDT0000", "job_stats:\n").is_empty());
+}
@@ -1,10 +1,24 @@
 use {
-    crate::units::Units,
-    lfs_core::*,
-    serde_json::{json, Value},
+    crate::{
+        changelog::ChangelogUser,
+        col::{Col, TableCol},
+        col_value::ColValue,
+        cols::Cols,
+        jobstats::JobStat,
+        mount_row::MountRow,
+        num_expr::NumKind,
+        server_targets::ServerTarget,
+        totals::Totals,
+        units::Units,
+    },
+    serde_json::{json, Map, Value},
 };
 
-pub fn output_value(mounts: &[&Mount], units: Units) -> Value {
+/// The version of the structure of the mounts and totals records,
+/// incremented on every change a parser could notice
+pub const FORMAT_VERSION: u32 = 3;
+
+pub fn output_value(mounts: &[&MountRow], units: Units) -> Value {
     Value::Array(
         mounts
             .iter()
@@ -15,7 +29,9 @@ pub fn output_value(mounts: &[&Mount], units: Units) -> Value {
                             "files": inodes.files,
                             "free": inodes.ffree,
                             "avail": inodes.favail,
+                            "used": inodes.used(),
                             "used-percent": format!("{:.0}%", 100.0*inodes.use_share()),
+                            "used-share": inodes.use_share(),
                         })
                     });
                     json!({
@@ -24,9 +40,13 @@ pub fn output_value(mounts: &[&Mount], units: Units) -> Value {
                         "bfree": s.bfree,
                         "bavail": s.bavail,
                         "size": units.fmt(s.size()),
+                        "size-bytes": s.size(),
                         "used": units.fmt(s.used()),
+                        "used-bytes": s.used(),
                         "used-percent": format!("{:.0}%", 100.0*s.use_share()),
+                        "used-share": s.use_share(),
                         "available": units.fmt(s.available()),
+                        "available-bytes": s.available(),
                         "inodes": inodes,
                     })
                 });
@@ -39,38 +59,558 @@ pub fn output_value(mounts: &[&Mount], units: Units) -> Value {
                         "ram": d.ram,
                     })
                 });
-                let mount_point_str = mount.info.mount_point.to_string_lossy();
-                let lustre_info = crate::get_lustre_info(&mount_point_str).map(|info| {
+                let value = |col: Col| col.value(mount).to_json();
+                let lustre_info = mount.lustre.as_ref().map(|info| {
                     json!({
-                        "stripe-count": info.stripe_count,
-                        "stripe-size": info.stripe_size,
-                        "lustre-version": info.lustre_version,
-                        "pool-name": info.pool_name,
-                        "component-type": info.component_type,
-                        "component-index": info.component_index,
-                        "mirror-count": info.mirror_count,
+                        "stripe-count": value(Col::StripeCount),
+                        "stripe-size": value(Col::StripeSize),
+                        "lustre-version": value(Col::LustreVersion),
+                        "pool-name": value(Col::PoolName),
+                        "component-type": value(Col::ComponentType),
+                        "component-index": value(Col::ComponentIndex),
+                        "target": value(Col::Target),
+                        "mirror-count": value(Col::MirrorCount),
+                        "hsm": info.hsm.as_ref().map(|hsm| json!({
+                            "path": hsm.path,
+                            "exists": hsm.exists(),
+                            "archived": hsm.archived(),
+                            "released": hsm.released(),
+                            "dirty": hsm.dirty(),
+                            "lost": hsm.lost(),
+                            "archive-id": hsm.archive_id,
+                        })),
+                        "hsm-coordinator": info.hsm_coordinator.as_ref().map(|coordinator| json!({
+                            "state": coordinator.state,
+                            "pending-actions": coordinator.pending_actions,
+                            "active-actions": coordinator.active_actions,
+                        })),
                     })
                 });
                 
                 json!({
-                    "id": mount.info.id,
+                    "format-version": FORMAT_VERSION,
+                    "id": value(Col::Id),
                     "dev": {
                         "major": mount.info.dev.major,
                         "minor": mount.info.dev.minor,
                     },
-                    "fs": mount.info.fs,
-                    "fs-label": mount.fs_label,
-                    "fs-type": mount.info.fs_type,
-                    "mount-point": mount.info.mount_point,
-                    "fs-name": crate::col::extract_fsname(&mount),
+                    "fs": value(Col::Filesystem),
+                    "fs-label": value(Col::Label),
+                    "fs-type": value(Col::Type),
+                    "mount-point": value(Col::MountPoint),
+                    "mounts": value(Col::Mounts),
+                    "fs-name": value(Col::FsName),
                     "disk": disk,
                     "stats": stats,
                     "lustre": lustre_info,
                     "bound": mount.info.bound,
-                    "remote": mount.info.is_remote(),
+                    "remote": value(Col::Remote),
                     "unreachable": mount.is_unreachable(),
                 })
             })
             .collect(),
     )
 }
+
+/// The mounts, with only the given columns when there are some
+pub fn mounts_value(mounts: &[&MountRow], units: Units, cols: Option<&Cols>) -> Value {
+    match cols {
+        Some(cols) => cols_value(mounts, units, cols),
+        None => output_value(mounts, units),
+    }
+}
+
+/// The key of a column in the records, its name in kebab-case
+/// like the keys of the complete structure
+fn col_key(col: Col) -> String {
+    col.name().replace('_', "-")
+}
+
+/// A record made of the values of the given columns.
+///
+/// Like in the complete structure, a size is given formatted under the
+/// key of its column and as a number of bytes under `<key>-bytes`, and
+/// a share is given as a percentage and under `<key>-share`, so that a
+/// key always has the same type
+fn cols_record<F>(cols: &Cols, units: Units, value: F) -> Value
+where
+    F: Fn(Col) -> ColValue,
+{
+    let mut record = Map::new();
+    record.insert("format-version".to_string(), json!(FORMAT_VERSION));
+    for &col in cols.cols() {
+        let key = col_key(col);
+        let value = value(col);
+        let suffix = if matches!(col, Col::Used | Col::Free | Col::Size | Col::StripeSize) {
+            Some("bytes")
+        } else if col.num_kind() == Some(NumKind::Share) {
+            Some("share")
+        } else {
+            None
+        };
+        match suffix {
+            Some(suffix) => {
+                record.insert(format!("{key}-{suffix}"), value.to_json());
+                record.insert(key, json!(value.display(units)));
+            }
+            None => {
+                record.insert(key, value.to_json());
+            }
+        }
+    }
+    Value::Object(record)
+}
+
+/// The values of the given columns of the mounts, by column name
+pub fn cols_value(mounts: &[&MountRow], units: Units, cols: &Cols) -> Value {
+    Value::Array(
+        mounts
+            .iter()
+            .map(|mount| cols_record(cols, units, |col| col.value(mount)))
+            .collect(),
+    )
+}
+
+/// The totals of the mounts, with only the given columns when there are some
+fn mounts_totals_value(mounts: &[&MountRow], units: Units, cols: Option<&Cols>) -> Value {
+    let totals = Totals::compute(mounts);
+    match cols {
+        Some(cols) => cols_record(cols, units, |col| totals.value(col, false)),
+        None => totals_value(&totals, units),
+    }
+}
+
+/// The mounts and the totals of their stats, for `--total`
+pub fn output_value_with_totals(mounts: &[&MountRow], units: Units, cols: Option<&Cols>) -> Value {
+    json!({
+        "mounts": mounts_value(mounts, units, cols),
+        "totals": mounts_totals_value(mounts, units, cols),
+    })
+}
+
+/// The mounts then a record with their totals, for a stream
+pub fn output_records_with_totals(mounts: &[&MountRow], units: Units, cols: Option<&Cols>) -> Value {
+    let mut records = match mounts_value(mounts, units, cols) {
+        Value::Array(records) => records,
+        value => vec![value],
+    };
+    records.push(json!({
+        "totals": mounts_totals_value(mounts, units, cols),
+    }));
+    Value::Array(records)
+}
+
+pub fn totals_value(totals: &Totals, units: Units) -> Value {
+    let inodes = totals.inodes.map(|inodes| {
+        json!({
+            "files": inodes.files,
+            "used": inodes.used,
+            "avail": inodes.favail,
+            "used-percent": format!("{:.0}%", 100.0*inodes.use_share()),
+            "used-share": inodes.use_share(),
+        })
+    });
+    json!({
+        "format-version": FORMAT_VERSION,
+        "count": totals.count,
+        "size": units.fmt(totals.size),
+        "size-bytes": totals.size,
+        "used": units.fmt(totals.used),
+        "used-bytes": totals.used,
+        "used-percent": format!("{:.0}%", 100.0*totals.use_share()),
+        "used-share": totals.use_share(),
+        "available": units.fmt(totals.available),
+        "available-bytes": totals.available,
+        "inodes": inodes,
+    })
+}
+
+/// The JSON Schema of the documents written with `--json`, YAML, or
+/// TOML, and of the records of a NDJSON stream, with or without `-c`
+/// and `--total`, printed with `--json-schema`
+pub fn schema() -> Value {
+    let object = |properties: Value| {
+        let required: Vec<&String> = properties.as_object().unwrap().keys().collect();
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    };
+    let nullable = |mut schema: Value| {
+        let kind = schema["type"].clone();
+        schema["type"] = json!([kind, "null"]);
+        schema
+    };
+    let integer = || json!({ "type": "integer", "minimum": 0 });
+    let share = || json!({ "type": "number", "minimum": 0, "maximum": 1 });
+    let string = || json!({ "type": "string" });
+    let boolean = || json!({ "type": "boolean" });
+    let format_version = json!({ "const": FORMAT_VERSION });
+    let mount = object(json!({
+        "format-version": format_version,
+        "id": integer(),
+        "dev": object(json!({
+            "major": integer(),
+            "minor": integer(),
+        })),
+        "fs": string(),
+        "fs-label": nullable(string()),
+        "fs-type": string(),
+        "mount-point": string(),
+        "mounts": { "type": "array", "items": string() },
+        "fs-name": nullable(string()),
+        "disk": nullable(object(json!({
+            "type": string(),
+            "rotational": nullable(boolean()),
+            "removable": nullable(boolean()),
+            "crypted": boolean(),
+            "ram": boolean(),
+        }))),
+        "stats": nullable(object(json!({
+            "bsize": integer(),
+            "blocks": integer(),
+            "bfree": integer(),
+            "bavail": integer(),
+            "size": string(),
+            "size-bytes": integer(),
+            "used": string(),
+            "used-bytes": integer(),
+            "used-percent": string(),
+            "used-share": share(),
+            "available": string(),
+            "available-bytes": integer(),
+            "inodes": nullable(object(json!({
+                "files": integer(),
+                "free": integer(),
+                "avail": integer(),
+                "used": integer(),
+                "used-percent": string(),
+                "used-share": share(),
+            }))),
+        }))),
+        "lustre": nullable(object(json!({
+            "stripe-count": nullable(integer()),
+            "stripe-size": nullable(integer()),
+            "lustre-version": nullable(string()),
+            "pool-name": nullable(string()),
+            "component-type": { "enum": ["MDT", "OST", "CLIENT"] },
+            "component-index": nullable(integer()),
+            "target": nullable(string()),
+            "mirror-count": nullable(integer()),
+            "hsm": nullable(object(json!({
+                "path": string(),
+                "exists": boolean(),
+                "archived": boolean(),
+                "released": boolean(),
+                "dirty": boolean(),
+                "lost": boolean(),
+                "archive-id": integer(),
+            }))),
+            "hsm-coordinator": nullable(object(json!({
+                "state": string(),
+                "pending-actions": integer(),
+                "active-actions": integer(),
+            }))),
+        }))),
+        "bound": boolean(),
+        "remote": nullable(boolean()),
+        "unreachable": boolean(),
+    }));
+    let totals = object(json!({
+        "format-version": format_version,
+        "count": integer(),
+        "size": string(),
+        "size-bytes": integer(),
+        "used": string(),
+        "used-bytes": integer(),
+        "used-percent": string(),
+        "used-share": share(),
+        "available": string(),
+        "available-bytes": integer(),
+        "inodes": nullable(object(json!({
+            "files": integer(),
+            "used": integer(),
+            "avail": integer(),
+            "used-percent": string(),
+            "used-share": share(),
+        }))),
+    }));
+    // with -c, a record has the format version and the values of the
+    // chosen columns, keyed by their names
+    let cols_record = json!({
+        "type": "object",
+        "properties": {
+            "format-version": format_version,
+        },
+        "required": ["format-version"],
+        "additionalProperties": {
+            "type": ["string", "integer", "number", "boolean", "array", "null"],
+            "items": string(),
+        },
+    });
+    // in a NDJSON stream, each record tells where and when it was made
+    let stamped = |mut schema: Value| {
+        schema["properties"]["host"] = string();
+        schema["properties"]["timestamp"] = string();
+        let required = schema["required"].as_array_mut().unwrap();
+        required.push(json!("host"));
+        required.push(json!("timestamp"));
+        schema
+    };
+    let titled = |title: &str, mut schema: Value| {
+        schema["title"] = json!(title);
+        schema
+    };
+    let array = |items: &Value| json!({ "type": "array", "items": items });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "dysk",
+        "description": "The filesystems listed by dysk with --json, --format yaml, or \
+            --format toml (the whole document), or with --format ndjson (each line)",
+        "anyOf": [
+            titled("mounts", array(&mount)),
+            titled("mounts with totals", object(json!({
+                "mounts": array(&mount),
+                "totals": totals,
+            }))),
+            titled("chosen columns", array(&cols_record)),
+            titled("chosen columns with totals", object(json!({
+                "mounts": array(&cols_record),
+                "totals": cols_record,
+            }))),
+            titled("stream mount", stamped(mount.clone())),
+            titled("stream chosen columns", cols_record.clone()),
+            titled("stream totals", stamped(object(json!({
+                "totals": { "anyOf": [totals, cols_record] },
+            })))),
+        ],
+    })
+}
+
+pub fn jobs_value(jobs: &[&JobStat]) -> Value {
+    Value::Array(
+        jobs
+            .iter()
+            .map(|job| {
+                json!({
+                    "job-id": job.job_id,
+                    "target": job.target,
+                    "read-bytes": job.read_bytes,
+                    "write-bytes": job.write_bytes,
+                    "metadata-ops": job.metadata_ops,
+                })
+            })
+            .collect(),
+    )
+}
+
+pub fn server_targets_value(targets: &[ServerTarget], units: Units) -> Value {
+    Value::Array(
+        targets
+            .iter()
+            .map(|target| {
+                let recovery = target.recovery.as_ref().map(|r| {
+                    json!({
+                        "status": r.status,
+                        "completed-clients": r.completed_clients,
+                        "total-clients": r.total_clients,
+                    })
+                });
+                json!({
+                    "target": target.name.to_string(),
+                    "kind": target.name.kind.name(),
+                    "device": target.device,
+                    "fs-type": target.backend,
+                    "recovery": recovery,
+                    "exports": target.exports,
+                    "read-only": target.read_only,
+                    "size": target.size.map(|s| units.fmt(s)),
+                    "size-bytes": target.size,
+                    "available": target.available.map(|s| units.fmt(s)),
+                    "available-bytes": target.available,
+                    "mount-point": target.mount_point,
+                })
+            })
+            .collect(),
+    )
+}
+
+pub fn changelog_users_value(users: &[ChangelogUser]) -> Value {
+    Value::Array(
+        users
+            .iter()
+            .map(|user| {
+                json!({
+                    "mdt": user.mdt,
+                    "id": user.id,
+                    "last-cleared": user.last_cleared,
+                    "current-index": user.current_index,
+                    "lag": user.lag(),
+                    "idle-seconds": user.idle,
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+fn test_mount() -> MountRow {
+    crate::test_util::mount(21, "/srv/schema-test")
+        .disk(lfs_core::Disk {
+            rotational: Some(false),
+            removable: Some(false),
+            ram: false,
+            lvm: false,
+            crypted: false,
+        })
+        .stats(4096, 1000, 250, 200)
+        .inodes(100, 40, 40)
+        .row()
+}
+
+#[test]
+fn test_schema_matches_output() {
+    /// check that the value has the type and the properties of the schema
+    fn check(schema: &Value, value: &Value, path: &str) {
+        if let Some(schemas) = schema["anyOf"].as_array() {
+            assert!(!schemas.is_empty(), "no schema for {path}");
+            // the alternatives of the tests are told apart by their first key
+            let schema = schemas
+                .iter()
+                .find(|schema| {
+                    let first_key = value.as_object().and_then(|o| o.keys().next());
+                    schema["properties"].as_object().and_then(|p| p.keys().next()) == first_key
+                })
+                .unwrap_or(&schemas[0]);
+            return check(schema, value, path);
+        }
+        let types: Vec<&str> = match &schema["type"] {
+            Value::String(kind) => vec![kind],
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        let has_type = |kind: &str| match kind {
+            "integer" => value.is_u64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            "boolean" => value.is_boolean(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => value.is_null(),
+        };
+        assert!(types.is_empty() || types.iter().any(|&kind| has_type(kind)), "type of {path}");
+        if let Some(values) = schema["enum"].as_array() {
+            assert!(values.contains(value), "value of {path}");
+        }
+        if let Some(expected) = schema.get("const") {
+            assert_eq!(value, expected, "value of {path}");
+        }
+        if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+            for (i, value) in values.iter().enumerate() {
+                check(items, value, &format!("{path}[{i}]"));
+            }
+        }
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            return;
+        };
+        let Some(object) = value.as_object() else {
+            assert!(value.is_null(), "{path} should be an object");
+            return;
+        };
+        for required in schema["required"].as_array().unwrap() {
+            assert!(object.contains_key(required.as_str().unwrap()), "{path}.{required} is missing");
+        }
+        for (key, value) in object {
+            let path = format!("{path}.{key}");
+            match (properties.get(key), schema.get("additionalProperties")) {
+                (Some(schema), _) => check(schema, value, &path),
+                (None, Some(schema)) => check(schema, value, &path),
+                (None, None) => panic!("{path} isn't in the schema"),
+            }
+        }
+    }
+    let mut mount = test_mount();
+    let mut lustre = crate::LustreInfo::new();
+    lustre.stripe_count = Some(2);
+    lustre.pool_name = Some("flash".to_string());
+    lustre.hsm = Some(crate::hsm::HsmState {
+        path: "/srv/schema-test".into(),
+        flags: 0,
+        archive_id: 0,
+    });
+    mount.lustre = Some(lustre);
+    let schema = schema();
+    let shape = |title: &str| {
+        schema["anyOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|shape| shape["title"] == title)
+            .unwrap_or_else(|| panic!("no {title:?} in the schema"))
+    };
+    let value = output_value(&[&mount], Units::Si);
+    check(shape("mounts"), &value, "mounts");
+    let record = &value[0];
+    assert_eq!(record["format-version"], FORMAT_VERSION);
+    assert_eq!(record["lustre"]["component-type"], "CLIENT");
+    assert_eq!(record["stats"]["size-bytes"], 4_096_000);
+    assert_eq!(record["stats"]["used-bytes"], 3_072_000);
+    assert_eq!(record["stats"]["available-bytes"], 819_200);
+    assert_eq!(record["stats"]["inodes"]["used"], 60);
+    assert_eq!(record["stats"]["inodes"]["used-share"], 0.6);
+    let value = output_value_with_totals(&[&mount], Units::Si, None);
+    check(shape("mounts with totals"), &value, "document");
+    assert_eq!(value["totals"]["size-bytes"], 4_096_000);
+    assert_eq!(value["totals"]["format-version"], FORMAT_VERSION);
+    let cols: Cols = "fs+size+use+mounts+remote+hsm_state".parse().unwrap();
+    let value = mounts_value(&[&mount], Units::Si, Some(&cols));
+    check(shape("chosen columns"), &value, "chosen columns");
+    let value = output_value_with_totals(&[&mount], Units::Si, Some(&cols));
+    check(shape("chosen columns with totals"), &value, "chosen columns with totals");
+    let stamp = crate::stamp::Stamp {
+        host: "node12".to_string(),
+        timestamp: "2025-03-01T18:04:12Z".to_string(),
+        secs: 1_740_852_252,
+    };
+    for cols in [None, Some(&cols)] {
+        let Value::Array(records) = output_records_with_totals(&[&mount], Units::Si, cols) else {
+            panic!("records expected");
+        };
+        let [mount_record, totals_record] = records.as_slice() else {
+            panic!("a mount and the totals expected");
+        };
+        let mount_shape = if cols.is_some() { "stream chosen columns" } else { "stream mount" };
+        check(shape(mount_shape), &stamp.apply(mount_record.clone()), mount_shape);
+        check(shape("stream totals"), &stamp.apply(totals_record.clone()), "stream totals");
+    }
+}
+
+#[test]
+fn test_cols_value() {
+    let mount = test_mount();
+    let cols: Cols = "fs+size+use+inodes_free".parse().unwrap();
+    let value = mounts_value(&[&mount], Units::Si, Some(&cols));
+    let record = value[0].as_object().unwrap();
+    let keys: Vec<&String> = record.keys().collect();
+    let expected_keys = vec!["format-version", "fs", "inodes-free", "size", "size-bytes", "use", "use-share"];
+    assert_eq!(keys, expected_keys);
+    assert_eq!(record["format-version"], FORMAT_VERSION);
+    assert_eq!(record["fs"], "/dev/sda1");
+    // same types as in the complete structure
+    assert_eq!(record["size"], Units::Si.fmt(4_096_000));
+    assert_eq!(record["size-bytes"], 4_096_000);
+    assert_eq!(record["use"], "79%");
+    assert!(record["use-share"].is_f64());
+    assert_eq!(record["inodes-free"], 40);
+    let value = output_value_with_totals(&[&mount], Units::Si, Some(&cols));
+    assert_eq!(value["mounts"][0].as_object().unwrap().len(), expected_keys.len());
+    let totals = value["totals"].as_object().unwrap();
+    let keys: Vec<&String> = totals.keys().collect();
+    assert_eq!(keys, expected_keys);
+    assert_eq!(totals["format-version"], FORMAT_VERSION);
+    assert!(totals["fs"].is_null());
+    assert_eq!(totals["size"], Units::Si.fmt(4_096_000));
+    assert_eq!(totals["size-bytes"], 4_096_000);
+    assert_eq!(totals["inodes-free"], 40);
+}
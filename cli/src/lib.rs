@@ -1,17 +1,39 @@
 pub mod args;
+pub mod changelog;
 pub mod col;
 pub mod col_expr;
+pub mod col_value;
 pub mod cols;
+pub mod conf;
 pub mod csv;
+pub mod dedup;
 pub mod filter;
+pub mod filter_lexer;
+pub mod format;
 pub mod help;
+pub mod hsm;
+pub mod job_col;
+pub mod jobstats;
 pub mod json;
 pub mod list_cols;
+pub mod metrics;
+pub mod mount_row;
+pub mod lustre_mount;
+pub mod lustre_proc;
+pub mod lustre_target;
+pub mod natural;
 pub mod normal;
+pub mod num_expr;
 pub mod order;
+pub mod server_targets;
 pub mod sorting;
+pub mod stamp;
 pub mod table;
+#[cfg(test)]
+mod test_util;
+pub mod totals;
 pub mod units;
+pub mod view;
 
 use lfs_core::Mount;
 use rustreapi::LustrePath;
@@ -21,17 +43,28 @@ use rustreapi::{Layout, LayoutGetFlags};
 use {
     crate::{
         args::*,
+        col::Col,
+        col_expr::EvalExprError,
+        conf::Conf,
+        filter::Filter,
+        format::Format,
+        hsm::{HsmCoordinator, HsmState},
+        job_col::JobCol,
+        lustre_target::LustreTargetName,
+        mount_row::MountRow,
         normal::*,
+        sorting::Sorting,
+        stamp::Stamp,
     },
-    clap::Parser,
+    clap::{CommandFactory, FromArgMatches},
     std::{
         fs,
         os::unix::fs::MetadataExt,
+        path::Path,
+        thread,
+        time::Duration,
     },
 };
-use crate::cols::Cols;
-use std::collections::HashMap;
-use std::sync::{Mutex, LazyLock};
 
 /// Lustre-specific information for a mount
 #[derive(Debug, Clone)]
@@ -40,9 +73,15 @@ pub struct LustreInfo {
     pub stripe_size: Option<u64>,
     pub lustre_version: Option<String>,
     pub pool_name: Option<String>,
-    pub component_type: Option<String>,
-    pub component_index: Option<u32>,
+    /// the name of the Lustre filesystem, eg "scratch"
+    pub fsname: Option<String>,
+    /// the MDT or OST, None for the client mount
+    pub target: Option<LustreTargetName>,
     pub mirror_count: Option<u16>,
+    /// HSM state of the mount point, or of the path given as argument
+    pub hsm: Option<HsmState>,
+    /// only known on a node serving a MDT of the filesystem
+    pub hsm_coordinator: Option<HsmCoordinator>,
 }
 
 impl LustreInfo {
@@ -52,51 +91,33 @@ impl LustreInfo {
             stripe_size: None,
             lustre_version: None,
             pool_name: None,
-            component_type: None,
-            component_index: None,
+            fsname: None,
+            target: None,
             mirror_count: None,
+            hsm: None,
+            hsm_coordinator: None,
         }
     }
-}
-
-/// Global storage for Lustre-specific mount information
-static LUSTRE_INFO: LazyLock<Mutex<HashMap<String, LustreInfo>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
-
-/// Get Lustre info for a mount point
-pub fn get_lustre_info(mount_point: &str) -> Option<LustreInfo> {
-    LUSTRE_INFO.lock().ok()?.get(mount_point).cloned()
-}
-
-/// Set Lustre info for a mount point
-pub fn set_lustre_info(mount_point: String, info: LustreInfo) {
-    if let Ok(mut map) = LUSTRE_INFO.lock() {
-        map.insert(mount_point, info);
-    }
-}
-
-/// Helper function to parse Lustre component names
-fn parse_lustre_component(name: &str) -> Option<(String, u32)> {
-    // Handle names like "lustre-MDT0000_UUID" or "lustre-OST0001_UUID"
-    if let Some(dash_pos) = name.find('-') {
-        let after_dash = &name[dash_pos + 1..];
-        if let Some(underscore_pos) = after_dash.find('_') {
-            let component_part = &after_dash[..underscore_pos];
-
-            // Extract type (MDT/OST) and number
-            if component_part.len() >= 7 { // At least "MDT0000" or "OST0000"
-                let (comp_type, num_str) = component_part.split_at(3);
-                if let Ok(num) = num_str.parse::<u32>() {
-                    return Some((comp_type.to_string(), num));
-                }
-            }
+    /// "MDT", "OST", or "CLIENT"
+    pub fn component_type(&self) -> &'static str {
+        match &self.target {
+            Some(target) => target.kind.name(),
+            None => "CLIENT",
         }
     }
-    None
+    pub fn component_index(&self) -> Option<u32> {
+        self.target.as_ref().map(|target| target.index)
+    }
 }
 
 #[allow(clippy::match_like_matches_macro)]
 pub fn run() {
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let args = match Args::from_arg_matches(&matches) {
+        Ok(args) => args,
+        Err(e) => e.exit(),
+    };
+    // those don't depend on the configuration, which may be broken
     if args.version {
         println!("dysk {}", env!("CARGO_PKG_VERSION"));
         return;
@@ -106,12 +127,107 @@ pub fn run() {
         csi_reset();
         return;
     }
+    if args.json_schema {
+        println!("{}", serde_json::to_string_pretty(&json::schema()).unwrap());
+        return;
+    }
+    let conf = if args.no_config {
+        Conf::default()
+    } else {
+        match Conf::load() {
+            Ok(conf) => conf,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        }
+    };
+    let (args, lustre_args) = match conf.resolve(args, &matches) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
     if args.list_cols {
-        list_cols::print(args.color(), args.ascii);
+        if args.jobs {
+            list_cols::print_jobs(args.color(), args.ascii);
+        } else {
+            let col_sets = match conf.col_sets() {
+                Ok(col_sets) => col_sets,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return;
+                }
+            };
+            list_cols::print(&col_sets, args.color(), args.ascii);
+        }
         csi_reset();
         return;
     }
+    if args.jobs {
+        print_jobs(&args);
+        csi_reset();
+        return;
+    }
+    if args.server {
+        print_server_targets(&args);
+        csi_reset();
+        return;
+    }
+    if args.changelogs {
+        print_changelog_users(&args);
+        csi_reset();
+        return;
+    }
+    if [&args, &lustre_args].iter().any(|args| args.total && args.format().is_metrics()) {
+        eprintln!("--total isn't available with the influx and graphite formats");
+        return;
+    }
+    let sorting: Sorting = match args.sorting() {
+        Ok(sorting) => sorting,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    // Lustre views show the MDTs, then the OSTs, then the client,
+    // unless another sorting is asked
+    let lustre_sorting: Sorting = match lustre_args.sort {
+        Some(_) => match lustre_args.sorting() {
+            Ok(sorting) => sorting,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        },
+        None => Sorting::lustre(),
+    };
+
+    // with --interval, the filesystems are listed again and again
+    let mut listings = 0;
+    loop {
+        if !print_mounts(&args, &lustre_args, &sorting, &lustre_sorting) {
+            return;
+        }
+        listings += 1;
+        let Some(interval) = args.interval else {
+            break;
+        };
+        if args.count.is_some_and(|count| listings >= count) {
+            break;
+        }
+        thread::sleep(Duration::from_secs(interval));
+    }
+}
 
+/// Print the filesystems, return false on error
+fn print_mounts(
+    args: &Args,
+    lustre_args: &Args,
+    sorting: &Sorting,
+    lustre_sorting: &Sorting,
+) -> bool {
     let mut options = lfs_core::ReadOptions::default();
     options.remote_stats(args.remote_stats.unwrap_or_else(||true));
 
@@ -119,16 +235,17 @@ pub fn run() {
         Ok(mounts) => mounts,
         Err(e) => {
             eprintln!("Error reading mounts: {}", e);
-            return;
+            return false;
         }
     };
 
+    // targets served by this node are shown with --server, not here
+    let lustre_mount_entries = lustre_mount::read_lustre_mounts(Path::new(lustre_mount::MOUNTINFO_PATH))
+        .unwrap_or_default();
     mounts.retain(|m| {
-        if m.info.fs_type == "lustre" {
-            !is_lustre_server_component(&m.info.mount_point)
-        } else {
-            true
-        }
+        !lustre_mount_entries
+            .iter()
+            .any(|lm| lm.id == m.info.id && lm.kind.is_server())
     });
 
     let lustre_mounts = MountStats::discover_mounts().unwrap_or_else(|e| {
@@ -158,66 +275,17 @@ pub fn run() {
     if !args.all {
         if has_lustre_mounts {
             mounts.retain(|m| m.info.fs_type == "lustre");
-
-            mounts.sort_by(|a, b| {
-                let a_name = &a.info.fs;
-                let b_name = &b.info.fs;
-
-                // Client mount (filesystem_summary) should come LAST
-                let a_is_client = a_name == "filesystem_summary";
-                let b_is_client = b_name == "filesystem_summary";
-
-                match (a_is_client, b_is_client) {
-                    (true, false) => std::cmp::Ordering::Greater,
-                    (false, true) => std::cmp::Ordering::Less,
-                    (true, true) => a_name.cmp(b_name),
-                    (false, false) => {
-                        let a_parts = parse_lustre_component(a_name);
-                        let b_parts = parse_lustre_component(b_name);
-
-                        match (a_parts, b_parts) {
-                            (Some((a_type, a_idx)), Some((b_type, b_idx))) => {
-                                // MDTs come before OSTs
-                                match (a_type.as_str(), b_type.as_str()) {
-                                    ("MDT", "OST") => std::cmp::Ordering::Less,
-                                    ("OST", "MDT") => std::cmp::Ordering::Greater,
-                                    _ => {
-                                        a_idx.cmp(&b_idx)
-                                    }
-                                }
-                            }
-                            _ => a_name.cmp(b_name)
-                        }
-                    }
-                }
-            });
         } else {
             mounts.retain(is_normal);
-            args.sort.sort(&mut mounts);
         }
-    } else {
-        args.sort.sort(&mut mounts);
     }
 
-    let final_args = if has_lustre_mounts && !args.all && mounts.iter().all(|m| m.info.fs_type == "lustre") {
-        if args.cols == Cols::default() {
-            let mut modified_args = args.clone();
-            modified_args.cols = "fs+used+use+free+size+fsname".parse().unwrap();
-            modified_args
-        } else {
-            let cols_str = format!("{:?}", args.cols);
-            if cols_str.starts_with('+') || cols_str.starts_with('-') {
-                let mut modified_args = args.clone();
-                let lustre_base = "fs+used+use+free+size+fsname";
-                let user_cols = "";
-                modified_args.cols = format!("{}+{}", lustre_base, user_cols).parse().unwrap();
-                modified_args
-            } else {
-                args.clone()
-            }
-        }
+    // a Lustre view has its own columns, filter, and sort
+    let is_lustre_only_view = has_lustre_mounts && !args.all;
+    let (final_args, final_sorting) = if is_lustre_only_view {
+        (lustre_args, lustre_sorting)
     } else {
-        args.clone()
+        (args, sorting)
     };
 
     if let Some(path) = &args.path {
@@ -225,7 +293,7 @@ pub fn run() {
             Ok(md) => md,
             Err(e) => {
                 eprintln!("Can't read {:?} : {}", path, e);
-                return;
+                return false;
             }
         };
         let dev = lfs_core::DeviceId::from(md.dev());
@@ -244,207 +312,241 @@ pub fn run() {
         }
     }
 
-    let is_lustre_only_view = has_lustre_mounts && !args.all &&
-        mounts.iter().all(|m| m.info.fs_type == "lustre");
-
-    if !is_lustre_only_view {
-        final_args.sort.sort(&mut mounts);
-    }
+    let filter: Filter = match final_args.row_filter() {
+        Ok(filter) => filter,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
 
-    let mounts = match final_args.filter.clone().unwrap_or_default().filter(&mounts) {
-        Ok(mounts) => mounts,
+    // reading the HSM state needs an ioctl per mount, it's
+    // only done when the HSM columns are used
+    let is_used = |cols: &[Col]| cols.iter().any(|&col| is_col_used(col, final_args, &filter, final_sorting));
+    let hsm_reads = HsmReads {
+        state: is_used(&[Col::HsmState, Col::HsmArchiveId]),
+        coordinator: is_used(&[Col::HsmCoordinator, Col::HsmPending, Col::HsmActive]),
+    };
+    // the rows, and their Lustre information, are rebuilt on every listing
+    let mut rows: Vec<MountRow> = mounts
+        .into_iter()
+        .map(|mount| lustre_row(mount, &lustre_mount_entries))
+        .collect();
+    collect_lustre_hsm_info(&mut rows, args.path.as_deref(), &args.lustre_proc(), hsm_reads);
+    let rows = match list_rows(rows, final_args, &filter, final_sorting) {
+        Ok(rows) => rows,
         Err(e) => {
             eprintln!("Error in filter evaluation: {}", e);
-            return;
+            return false;
         }
     };
 
-    // Deduplicate filesystems by default (keep only one mount per filesystem)
-    // TODO: Add a flag to disable deduplication if needed
-    let (mut mounts, mount_points_map) = check_duplicate(mounts.into_iter().cloned().collect());
+    let mount_refs: Vec<&MountRow> = rows.iter().collect();
     
-    // Re-apply Lustre sorting after deduplication if needed
-    if is_lustre_only_view {
-        mounts.sort_by(|a, b| {
-            let a_name = &a.info.fs;
-            let b_name = &b.info.fs;
-
-            // Client mount (filesystem_summary) should come LAST
-            let a_is_client = a_name == "filesystem_summary";
-            let b_is_client = b_name == "filesystem_summary";
-
-            match (a_is_client, b_is_client) {
-                (true, false) => std::cmp::Ordering::Greater,
-                (false, true) => std::cmp::Ordering::Less,
-                (true, true) => a_name.cmp(b_name),
-                (false, false) => {
-                    let a_parts = parse_lustre_component(a_name);
-                    let b_parts = parse_lustre_component(b_name);
-
-                    match (a_parts, b_parts) {
-                        (Some((a_type, a_idx)), Some((b_type, b_idx))) => {
-                            // MDTs come before OSTs
-                            match (a_type.as_str(), b_type.as_str()) {
-                                ("MDT", "OST") => std::cmp::Ordering::Less,
-                                ("OST", "MDT") => std::cmp::Ordering::Greater,
-                                _ => {
-                                    a_idx.cmp(&b_idx)
-                                }
-                            }
-                        }
-                        _ => a_name.cmp(b_name)
-                    }
-                }
-            }
-        });
+    match final_args.format() {
+        Format::Table => {}
+        Format::Csv => {
+            csv::print(&mount_refs, final_args).expect("writing csv failed");
+            return true;
+        }
+        Format::Influx => {
+            print!("{}", metrics::influx(&mount_refs, &Stamp::now()));
+            return true;
+        }
+        Format::Graphite => {
+            print!("{}", metrics::graphite(&mount_refs, &Stamp::now()));
+            return true;
+        }
+        format => {
+            let units = final_args.units;
+            let cols = final_args.serialized_cols();
+            let value = match (final_args.total, format) {
+                (false, _) => json::mounts_value(&mount_refs, units, cols),
+                // in a stream, the totals are one more record
+                (true, Format::Ndjson) => json::output_records_with_totals(&mount_refs, units, cols),
+                (true, _) => json::output_value_with_totals(&mount_refs, units, cols),
+            };
+            return print_serialized(format, "mounts", value);
+        }
     }
-    
-    // Convert back to the expected &[&Mount] format for the output functions
-    let mount_refs: Vec<&Mount> = mounts.iter().collect();
-    
-    if final_args.csv {
-        csv::print(&mount_refs, &final_args).expect("writing csv failed");
-        return;
+    if rows.is_empty() {
+        println!("no mount to display - try\n    dysk -a");
+        return true;
     }
-    if final_args.json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&json::output_value(&mount_refs, final_args.units)).unwrap()
-        );
-        return;
+    table::print(&mount_refs, final_args.color(), final_args);
+    csi_reset();
+    true
+}
+
+/// Filter the rows, merge the ones of a same filesystem, and sort them
+fn list_rows(
+    rows: Vec<MountRow>,
+    args: &Args,
+    filter: &Filter,
+    sorting: &Sorting,
+) -> Result<Vec<MountRow>, EvalExprError> {
+    let rows = filter.filter(&rows)?;
+
+    // merge the mounts of a same filesystem, like bind mounts
+    let mut rows = dedup::deduplicate(
+        rows.into_iter().cloned().collect(),
+        args.dedup,
+        &args.prefer_mount,
+    );
+
+    // sorting comes last, so that the order doesn't depend on
+    // the previous transformations
+    sorting.sort(&mut rows);
+    Ok(rows)
+}
+
+/// Print the activity of jobs on the local Lustre targets
+fn print_jobs(args: &Args) {
+    let sorting: Sorting<JobCol> = match args.sorting() {
+        Ok(sorting) => sorting,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let filter: Filter<JobCol> = match args.row_filter() {
+        Ok(filter) => filter,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let mut jobs = match jobstats::read_job_stats(&args.lustre_proc()) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            eprintln!("Error reading job stats: {}", e);
+            return;
+        }
+    };
+    sorting.sort(&mut jobs);
+    let jobs = match filter.filter(&jobs) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            eprintln!("Error in filter evaluation: {}", e);
+            return;
+        }
+    };
+    match args.format() {
+        Format::Table => {}
+        Format::Csv => {
+            csv::print_jobs(&jobs, args).expect("writing csv failed");
+            return;
+        }
+        format if format.is_metrics() => {
+            eprintln!("The influx and graphite formats are only available for filesystems");
+            return;
+        }
+        format => {
+            print_serialized(format, "jobs", json::jobs_value(&jobs));
+            return;
+        }
     }
-    if mounts.is_empty() {
-        println!("no mount to display - try\n    dysk -a");
+    if jobs.is_empty() {
+        println!("no job stats found - jobstats must be enabled on the local Lustre targets (see `lctl get_param jobid_var`)");
         return;
     }
-    table::print(&mount_refs, final_args.color(), &final_args);
-    
-    // Print mount points summary at the end
-    print_mount_points_summary(&mount_points_map);
-    
-    csi_reset();
+    table::print_jobs(&jobs, args.color(), args);
 }
 
-/// Deduplicate filesystems - keep only one mount per filesystem
-/// For Lustre, keep all components separate (don't deduplicate)
-/// For others, group by device ID and prefer the shortest/root mount path
-/// Returns (deduplicated_mounts, mount_points_map)
-fn check_duplicate(mounts: Vec<Mount>) -> (Vec<Mount>, std::collections::HashMap<String, Vec<String>>) {
-    use std::collections::HashMap;
-    
-    let mut filesystem_map: HashMap<String, Vec<Mount>> = HashMap::new();
-    let mut mount_points_map = HashMap::new();
-    
-    // Group mounts by appropriate key
-    for mount in mounts {
-        let group_key = if mount.info.fs_type == "lustre" {
-            // For Lustre, keep each component separate (don't group them)
-            // Use a unique key for each mount to prevent deduplication
-            format!("lustre_{}_{}", mount.info.fs, mount.info.mount_point.to_string_lossy())
-        } else {
-            // For non-Lustre, group by device ID to handle bind mounts
-            format!("{}:{}", mount.info.dev.major, mount.info.dev.minor)
-        };
-        
-        filesystem_map
-            .entry(group_key)
-            .or_insert_with(Vec::new)
-            .push(mount);
+/// Print the MDT and OST served by this node
+fn print_server_targets(args: &Args) {
+    let mut targets = match server_targets::read_server_targets(&args.lustre_proc()) {
+        Ok(targets) => targets,
+        Err(e) => {
+            eprintln!("Error reading Lustre targets: {}", e);
+            return;
+        }
+    };
+    // the mount points aren't in the Lustre parameters, we find
+    // them by looking for the mount serving the target
+    if let Ok(mounts) = lustre_mount::read_lustre_mounts(Path::new(lustre_mount::MOUNTINFO_PATH)) {
+        for target in &mut targets {
+            target.mount_point = mounts
+                .iter()
+                .find(|m| m.svname.as_ref() == Some(&target.name.to_string()))
+                .map(|m| m.mount_point.clone());
+        }
     }
-    
-    let mut deduplicated = Vec::new();
-    
-    for (_group_key, group) in filesystem_map {
-        if group.len() == 1 {
-            // Only one mount for this filesystem, keep it
-            let mount = &group[0];
-            let fs_name = crate::col::extract_fsname(mount);
-            if mount.info.fs_type == "lustre" {
-                // For Lustre, don't add to mount_points_map as we want individual components shown
-                deduplicated.extend(group);
-            } else {
-                mount_points_map.insert(fs_name, vec![mount.info.mount_point.to_string_lossy().to_string()]);
-                deduplicated.extend(group);
-            }
-        } else {
-            // Multiple mounts for this filesystem, choose the best representative
-            let mount_points: Vec<String> = group.iter()
-                .map(|m| m.info.mount_point.to_string_lossy().to_string())
-                .collect();
-            let representative = choose_representative_mount(group);
-            let fs_name = crate::col::extract_fsname(&representative);
-            mount_points_map.insert(fs_name, mount_points);
-            deduplicated.push(representative);
+    match args.format() {
+        Format::Table => {}
+        Format::Csv => {
+            csv::print_server_targets(&targets, args).expect("writing csv failed");
+            return;
+        }
+        format if format.is_metrics() => {
+            eprintln!("The influx and graphite formats are only available for filesystems");
+            return;
+        }
+        format => {
+            print_serialized(format, "targets", json::server_targets_value(&targets, args.units));
+            return;
         }
     }
-    
-    (deduplicated, mount_points_map)
+    if targets.is_empty() {
+        println!("no Lustre target served by this node");
+        return;
+    }
+    table::print_server_targets(&targets, args.color(), args);
 }
 
-/// Choose the best representative mount from a group of mounts for the same filesystem
-fn choose_representative_mount(mut mounts: Vec<Mount>) -> Mount {
-    // For Lustre filesystems, prefer filesystem_summary (client mount)
-    if let Some(pos) = mounts.iter().position(|m| m.info.fs == "filesystem_summary") {
-        return mounts.remove(pos);
-    }
-    
-    // For other filesystems, prefer the mount with the shortest path (usually the root mount)
-    mounts.sort_by(|a, b| {
-        let a_path = a.info.mount_point.to_string_lossy();
-        let b_path = b.info.mount_point.to_string_lossy();
-        
-        // Prefer root mounts
-        match (a_path.as_ref(), b_path.as_ref()) {
-            ("/", _) => std::cmp::Ordering::Less,
-            (_, "/") => std::cmp::Ordering::Greater,
-            _ => a_path.len().cmp(&b_path.len()).then_with(|| a_path.cmp(&b_path))
+/// Print the changelog users of the local MDTs, the most late first
+fn print_changelog_users(args: &Args) {
+    let mut users = match changelog::read_changelog_users(&args.lustre_proc()) {
+        Ok(users) => users,
+        Err(e) => {
+            eprintln!("Error reading changelog users: {}", e);
+            return;
         }
-    });
-    
-    mounts.into_iter().next().unwrap()
+    };
+    users.sort_by(|a, b| b.lag().cmp(&a.lag()).then_with(|| a.mdt.cmp(&b.mdt)));
+    match args.format() {
+        Format::Table => {}
+        Format::Csv => {
+            csv::print_changelog_users(&users, args).expect("writing csv failed");
+            return;
+        }
+        format if format.is_metrics() => {
+            eprintln!("The influx and graphite formats are only available for filesystems");
+            return;
+        }
+        format => {
+            print_serialized(format, "changelog-users", json::changelog_users_value(&users));
+            return;
+        }
+    }
+    if users.is_empty() {
+        println!("no changelog user registered on a local MDT");
+        return;
+    }
+    table::print_changelog_users(&users, args.color(), args);
 }
 
-/// Extract component type and index from mount point path
-fn extract_component_info(mount_point: &str) -> (Option<String>, Option<u32>) {
-    if mount_point.contains("[MDT:") {
-        if let Some(start) = mount_point.find("[MDT:") {
-            if let Some(end) = mount_point.find("]") {
-                let index_str = &mount_point[start + 5..end];
-                if let Ok(index) = index_str.parse::<u32>() {
-                    return (Some("MDT".to_string()), Some(index));
-                }
-            }
+/// Print the value in a serialization format, return false on error
+fn print_serialized(format: Format, root_key: &str, value: serde_json::Value) -> bool {
+    match format.serialize(root_key, value) {
+        Ok(s) => {
+            print!("{}", s);
+            true
         }
-        return (Some("MDT".to_string()), None);
-    } else if mount_point.contains("[OST:") {
-        if let Some(start) = mount_point.find("[OST:") {
-            if let Some(end) = mount_point.find("]") {
-                let index_str = &mount_point[start + 5..end];
-                if let Ok(index) = index_str.parse::<u32>() {
-                    return (Some("OST".to_string()), Some(index));
-                }
-            }
+        Err(e) => {
+            eprintln!("{}", e);
+            false
         }
-        return (Some("OST".to_string()), None);
-    } else if !mount_point.contains("[") {
-        return (Some("CLIENT".to_string()), None);
     }
-    
-    (None, None)
 }
 
 /// Collect Lustre layout information for a mount point
-fn collect_lustre_layout_info(mount_point: &str) -> LustreInfo {
+fn collect_lustre_layout_info(mount_point: &str, target: Option<LustreTargetName>) -> LustreInfo {
     let mut info = LustreInfo::new();
     
-    // Extract component type and index for all mounts
-    let (comp_type, comp_index) = extract_component_info(mount_point);
-    info.component_type = comp_type;
-    info.component_index = comp_index;
-    
     // Only collect stripe/layout information for actual client mounts (not component mounts)
     // The filesystem_summary represents the client view, so include it
-    if !mount_point.contains("[") {
+    if target.is_none() {
         // Try to get layout information for the mount point
         if let Ok(layout) = Layout::with_path(std::path::Path::new(mount_point), LayoutGetFlags::NONE) {
             // Get stripe count
@@ -503,10 +605,66 @@ fn collect_lustre_layout_info(mount_point: &str) -> LustreInfo {
     }
     // For component mounts (MDT/OST), don't set stripe information or version
     // since the version is the same across the filesystem and should only be shown once
+    info.target = target;
     
     info
 }
 
+/// Whether the values of a column are needed, because they're
+/// displayed, filtered, or sorted on
+fn is_col_used(col: Col, args: &Args, filter: &Filter, sorting: &Sorting) -> bool {
+    let displayed = match args.format() {
+        Format::Table | Format::Csv => args.cols.contains(col),
+        Format::Influx | Format::Graphite => false,
+        // without chosen columns, the serialized outputs have everything
+        _ => match args.serialized_cols() {
+            Some(cols) => cols.contains(col),
+            None => true,
+        },
+    };
+    displayed || filter.uses(col) || sorting.keys().iter().any(|key| key.col == col)
+}
+
+/// Which HSM information is read for the Lustre client mounts
+#[derive(Debug, Clone, Copy)]
+struct HsmReads {
+    /// the HSM state of the mount point or given path
+    state: bool,
+    /// the state of the coordinator, only known on a MDS
+    coordinator: bool,
+}
+
+/// Collect the HSM state of the Lustre client mounts (or of the given path
+/// when it's in one of them) and the state of their coordinators
+fn collect_lustre_hsm_info(
+    rows: &mut [MountRow],
+    path: Option<&Path>,
+    proc: &lustre_proc::LustreProc,
+    reads: HsmReads,
+) {
+    if !reads.state && !reads.coordinator {
+        return;
+    }
+    for row in rows {
+        let mount = &row.mount;
+        if mount.info.fs_type != "lustre" || is_lustre_component_mount(mount) {
+            continue;
+        }
+        let mut info = row.lustre.take().unwrap_or_else(LustreInfo::new);
+        let hsm_path = match path {
+            Some(path) if path.starts_with(&mount.info.mount_point) => path,
+            _ => &mount.info.mount_point,
+        };
+        if reads.state {
+            info.hsm = hsm::read_hsm_state(hsm_path).ok();
+        }
+        if let Some(fsname) = info.fsname.as_deref().filter(|_| reads.coordinator) {
+            info.hsm_coordinator = hsm::read_hsm_coordinator(proc, fsname).ok().flatten();
+        }
+        row.lustre = Some(info);
+    }
+}
+
 /// Convert rustreapi::Mount to lfs_core::Mount for integration
 fn convert_lustre_mount_to_lfs_mount(lustre_mount: &LustreMount) -> Mount {
     // Convert rustreapi types to lfs_core types
@@ -540,40 +698,42 @@ fn convert_lustre_mount_to_lfs_mount(lustre_mount: &LustreMount) -> Mount {
         inodes,
     };
 
-    let mount = Mount {
+    Mount {
         info: mount_info,
         fs_label: lustre_mount.fs_label.clone(),
         disk: None,
         stats: Ok(stats),
         uuid: lustre_mount.uuid.clone(),
         part_uuid: lustre_mount.part_uuid.clone(),
-    };
-
-    // Collect and store Lustre-specific information for all Lustre mounts
-    if lustre_mount.info.fs_type == "lustre" {
-        let mount_point = lustre_mount.info.mount_point.to_string_lossy().to_string();
-        let lustre_info = collect_lustre_layout_info(&mount_point);
-        set_lustre_info(mount_point, lustre_info);
     }
-
-    mount
 }
 
-/// Check if a mount point looks like a Lustre server component
-fn is_lustre_server_component(mount_point: &std::path::Path) -> bool {
-    let path_str = mount_point.to_string_lossy();
-    // Common patterns for Lustre server mounts
-    path_str.contains("-ost") ||
-        path_str.contains("-mdt") ||
-        path_str.contains("-mds") ||
-        path_str.contains("ost") && (path_str.contains("lustre") || path_str.contains("scratch")) ||
-        path_str.contains("mdt") && (path_str.contains("lustre") || path_str.contains("scratch"))
+/// The row of a mount, with the Lustre-specific information
+/// for the Lustre mounts
+fn lustre_row(mount: Mount, lustre_mount_entries: &[lustre_mount::LustreMountEntry]) -> MountRow {
+    let lustre = (mount.info.fs_type == "lustre").then(|| {
+        let mount_point = mount.info.mount_point.to_string_lossy();
+        let target: Option<LustreTargetName> = mount.info.fs.parse().ok();
+        // a client's fsname is in the source of its mountinfo line
+        let fsname = match &target {
+            Some(target) => Some(target.fsname.clone()),
+            None => lustre_mount_entries
+                .iter()
+                .find(|e| e.kind == lustre_mount::LustreMountKind::Client && e.mount_point == mount.info.mount_point)
+                .and_then(|e| e.fsname.clone()),
+        };
+        LustreInfo {
+            fsname,
+            ..collect_lustre_layout_info(&mount_point, target)
+        }
+    });
+    MountRow { lustre, ..MountRow::new(mount) }
 }
 
-/// Check if this is one of our component mounts (has [MDT:] or [OST:] in the path)
+/// Check if this is one of our component mounts, whose name
+/// is the UUID of a MDT or OST
 fn is_lustre_component_mount(mount: &Mount) -> bool {
-    let path_str = mount.info.mount_point.to_string_lossy();
-    path_str.contains("[MDT:") || path_str.contains("[OST:")
+    mount.info.fs.parse::<LustreTargetName>().is_ok()
 }
 
 /// Replace Lustre client mounts with API-enhanced versions that have better stats
@@ -594,21 +754,66 @@ fn replace_lustre_client_mounts(mounts: &mut Vec<Mount>, lustre_mounts: &Vec<Mou
     }
 }
 
-/// Print a summary of mount points for filesystems that have multiple mounts
-fn print_mount_points_summary(mount_points_map: &std::collections::HashMap<String, Vec<String>>) {
-    let multi_mount_filesystems: Vec<_> = mount_points_map.iter()
-        .filter(|(_, mount_points)| mount_points.len() > 1)
-        .collect();
-    
-    if !multi_mount_filesystems.is_empty() {
-        println!("\nMount Points:");
-        for (fs_name, mount_points) in multi_mount_filesystems {
-            println!("  {}: {}", fs_name, mount_points.join(", "));
-        }
-    }
-}
-
 /// output a Reset CSI sequence
 fn csi_reset(){
     print!("\u{1b}[0m");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_listings_dont_share_rows() {
+    use {
+        clap::Parser,
+        std::path::PathBuf,
+    };
+    let row = |id, minor, mount_point: &str, fs_type: &str| {
+        test_util::mount(id, mount_point)
+            .dev(8, minor)
+            .fs(&format!("/dev/sd{minor}"))
+            .fs_type(fs_type)
+            .row()
+    };
+    let args = Args::try_parse_from(["dysk"]).unwrap();
+    let list = |rows| list_rows(rows, &args, &Filter::default(), &Sorting::default()).unwrap();
+    let mut client = row(3, 3, "/srv/listing-test/lustre", "lustre");
+    let mut info = LustreInfo::new();
+    info.pool_name = Some("flash".to_string());
+    client.lustre = Some(info);
+    let first = list(vec![
+        row(1, 1, "/srv/listing-test", "ext4"),
+        row(2, 1, "/srv/listing-test/bind", "ext4"),
+        client,
+    ]);
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[0].mount_points.len(), 2);
+    assert_eq!(Col::PoolName.value(&first[1]), col_value::ColValue::Text("flash".to_string()));
+    // the bind mount and the Lustre pool are gone in the next listing
+    let second = list(vec![
+        row(2, 1, "/srv/listing-test/bind", "ext4"),
+        row(3, 3, "/srv/listing-test/lustre", "lustre"),
+    ]);
+    assert_eq!(second.len(), 2);
+    assert_eq!(second[0].mount_points, vec![PathBuf::from("/srv/listing-test/bind")]);
+    assert_eq!(Col::PoolName.value(&second[1]), col_value::ColValue::Missing);
+}
+
+#[test]
+fn test_hsm_is_read_for_the_full_serialized_outputs() {
+    use clap::{CommandFactory, FromArgMatches};
+    let used = |argv: &[&str]| {
+        let matches = Args::command().try_get_matches_from(argv).unwrap();
+        let args = Args::from_arg_matches(&matches).unwrap();
+        let (args, _) = Conf::default().resolve(args, &matches).unwrap();
+        let filter: Filter = args.row_filter().unwrap();
+        [Col::HsmState, Col::HsmCoordinator]
+            .map(|col| is_col_used(col, &args, &filter, &Sorting::default()))
+    };
+    // without -c, `hsm` and `hsm-coordinator` are always filled when known
+    assert_eq!(used(&["dysk", "-j"]), [true, true]);
+    assert_eq!(used(&["dysk", "--format", "yaml"]), [true, true]);
+    assert_eq!(used(&["dysk", "--format", "ndjson"]), [true, true]);
+    // otherwise, only when they're displayed or filtered on
+    assert_eq!(used(&["dysk"]), [false, false]);
+    assert_eq!(used(&["dysk", "-j", "-c", "fs+size"]), [false, false]);
+    assert_eq!(used(&["dysk", "-j", "-c", "fs+hsm"]), [true, false]);
+    assert_eq!(used(&["dysk", "-f", "hsm_state=released"]), [true, false]);
+}
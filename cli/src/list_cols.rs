@@ -1,5 +1,9 @@
 use {
-    crate::col::ALL_COLS,
+    crate::{
+        col::ALL_COLS,
+        cols::ColSets,
+        job_col::ALL_JOB_COLS,
+    },
     termimad::{
         minimad::OwningTemplateExpander,
         MadSkin,
@@ -22,10 +26,35 @@ ${column
 |${name}|${aliases}|${default}|${description}
 }
 |-
+
+Sets of columns can be used like columns, eg `dysk -c +lustre-ids`.
+You can define your own sets in the configuration.
+`inodes` is a column, the set of all inode columns is `inodes_all`.
+
+|:-:|:-
+|set | columns
+|:-:|-
+${set
+|${name}|${cols}
+}
+|-
+"#;
+
+static JOBS_MD: &str = r#"
+The jobs view (`dysk --jobs`) always shows all its columns, which can't be chosen with `--cols`.
+They can be used in `--sort` and `--filter`, for example `dysk --jobs -s ops -f 'target=OST'`
+
+|:-:|:-:|:-
+|column | aliases | content
+|:-:|:-:|-
+${column
+|${name}|${aliases}|${description}
+}
+|-
 "#;
 
-/// Print an help text describing columns
-pub fn print(color: bool, ascii: bool) {
+/// Print an help text describing columns and sets of columns
+pub fn print(col_sets: &ColSets, color: bool, ascii: bool) {
     let mut expander = OwningTemplateExpander::new();
     expander.set_default("");
     for &col in ALL_COLS {
@@ -35,6 +64,12 @@ pub fn print(color: bool, ascii: bool) {
             .set("default", if col.is_default() { "x" } else { "" })
             .set("description", col.description());
     }
+    for set in col_sets.sets() {
+        let cols: Vec<&str> = set.cols.iter().map(|col| col.name()).collect();
+        expander.sub("set")
+            .set("name", &set.name)
+            .set("cols", cols.join("+"));
+    }
     let mut skin = if color {
         MadSkin::default()
     } else {
@@ -46,3 +81,22 @@ pub fn print(color: bool, ascii: bool) {
     skin.print_owning_expander_md(&expander, MD);
 }
 
+/// Print an help text describing the columns of the jobs view
+pub fn print_jobs(color: bool, ascii: bool) {
+    let mut expander = OwningTemplateExpander::new();
+    for &col in ALL_JOB_COLS {
+        expander.sub("column")
+            .set("name", col.name())
+            .set("aliases", col.aliases().join(", "))
+            .set("description", col.description());
+    }
+    let mut skin = if color {
+        MadSkin::default()
+    } else {
+        MadSkin::no_style()
+    };
+    if ascii {
+        skin.limit_to_ascii();
+    }
+    skin.print_owning_expander_md(&expander, JOBS_MD);
+}
@@ -0,0 +1,203 @@
+use {
+    crate::lustre_target::{LustreTargetName, TargetKind},
+    lfs_core::MountId,
+    std::{
+        fs,
+        io,
+        path::{Path, PathBuf},
+    },
+};
+
+pub static MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+
+/// The role of a Lustre mount on this node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LustreMountKind {
+    /// a mount of the filesystem, as used by applications
+    Client,
+    Mdt,
+    Ost,
+    Mgs,
+}
+
+impl LustreMountKind {
+    pub fn is_server(self) -> bool {
+        self != Self::Client
+    }
+}
+
+/// A Lustre mount as described in mountinfo
+#[derive(Debug, Clone, PartialEq)]
+pub struct LustreMountEntry {
+    pub id: MountId,
+    pub mount_point: PathBuf,
+    pub kind: LustreMountKind,
+    /// name of the served target, eg "lustre-OST0001" or "MGS",
+    /// only for server mounts
+    pub svname: Option<String>,
+    /// name of the filesystem, eg "lustre", unknown for a MGS
+    pub fsname: Option<String>,
+}
+
+/// Determine the role of a mount from its filesystem type, its
+/// source, and its options (as found in mountinfo).
+///
+/// Return None when it's not a Lustre mount or its role can't be
+/// determined.
+pub fn classify(fs_type: &str, source: &str, options: &str) -> Option<LustreMountKind> {
+    if fs_type != "lustre" && fs_type != "lustre_tgt" {
+        return None;
+    }
+    let mut svname = None;
+    let mut is_mgs = false;
+    for option in options.split(',') {
+        if let Some(value) = option.strip_prefix("svname=") {
+            svname = Some(value);
+        } else if option == "mgs" {
+            is_mgs = true;
+        }
+    }
+    if let Some(svname) = svname {
+        // a combined MGS+MDT mount has svname=<fsname>-MDT<idx> and the mgs flag,
+        // it's reported as a MDT
+        if svname == "MGS" {
+            return Some(LustreMountKind::Mgs);
+        }
+        return svname
+            .parse::<LustreTargetName>()
+            .ok()
+            .map(|target| match target.kind {
+                TargetKind::Mdt => LustreMountKind::Mdt,
+                TargetKind::Ost => LustreMountKind::Ost,
+            });
+    }
+    if is_mgs {
+        return Some(LustreMountKind::Mgs);
+    }
+    if fs_type == "lustre" && source.contains(":/") {
+        // clients mount <mgsnid>[:<mgsnid>]:/<fsname>
+        return Some(LustreMountKind::Client);
+    }
+    None
+}
+
+/// Read the Lustre mounts of this node
+pub fn read_lustre_mounts(mountinfo_path: &Path) -> io::Result<Vec<LustreMountEntry>> {
+    let content = fs::read_to_string(mountinfo_path)?;
+    Ok(content.lines().filter_map(parse_mountinfo_line).collect())
+}
+
+/// Parse a line of mountinfo, keeping it only if it's a Lustre mount.
+///
+/// A line looks like
+/// `36 35 98:0 / /mnt/ost0 rw,relatime shared:1 - lustre /dev/sdb ro,svname=lustre-OST0000,osd=osd-ldiskfs`
+fn parse_mountinfo_line(line: &str) -> Option<LustreMountEntry> {
+    let (before, after) = line.split_once(" - ")?;
+    let mut before = before.split_whitespace();
+    let id = before.next()?.parse().ok()?;
+    let mount_point = before.nth(3)?;
+    let mount_options = before.next()?;
+    let mut after = after.split_whitespace();
+    let fs_type = after.next()?;
+    let source = after.next()?;
+    let super_options = after.next().unwrap_or("");
+    let options = format!("{mount_options},{super_options}");
+    let kind = classify(fs_type, source, &options)?;
+    let svname = options
+        .split(',')
+        .find_map(|option| option.strip_prefix("svname="))
+        .map(|svname| svname.to_string());
+    let fsname = match kind {
+        LustreMountKind::Client => source
+            .rsplit_once(":/")
+            .map(|(_, fsname)| fsname.to_string()),
+        _ => svname
+            .as_ref()
+            .and_then(|svname| svname.parse::<LustreTargetName>().ok())
+            .map(|target| target.fsname),
+    };
+    Some(LustreMountEntry {
+        id,
+        mount_point: PathBuf::from(unescape(mount_point)),
+        kind,
+        svname,
+        fsname,
+    })
+}
+
+/// Unescape the octal sequences used by the kernel for
+/// spaces and other special chars in paths (eg `\040`)
+fn unescape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(idx) = rest.find('\\') {
+        res.push_str(&rest[..idx]);
+        let code = rest.get(idx + 1..idx + 4).and_then(|o| u8::from_str_radix(o, 8).ok());
+        match code {
+            Some(code) => {
+                res.push(code as char);
+                rest = &rest[idx + 4..];
+            }
+            None => {
+                res.push('\\');
+                rest = &rest[idx + 1..];
+            }
+        }
+    }
+    res.push_str(rest);
+    res
+}
+
+#[test]
+fn test_classify() {
+    use LustreMountKind::*;
+    // clients
+    assert_eq!(classify("lustre", "10.0.0.1@tcp:/lustre", "rw,flock,lazystatfs"), Some(Client));
+    assert_eq!(classify("lustre", "10.0.0.1@o2ib:10.0.0.2@o2ib:/scratch", "rw"), Some(Client));
+    // servers, whatever their mount point
+    assert_eq!(classify("lustre", "/dev/sdb", "ro,svname=lustre-OST0000,mgsnode=10.0.0.1@tcp,osd=osd-ldiskfs"), Some(Ost));
+    assert_eq!(classify("lustre", "mdtpool/mdt0", "rw,svname=scratch-fs-MDT0000,osd=osd-zfs"), Some(Mdt));
+    assert_eq!(classify("lustre", "/dev/sdc", "rw,svname=MGS,osd=osd-ldiskfs"), Some(Mgs));
+    assert_eq!(classify("lustre", "/dev/sda", "rw,svname=lustre-MDT0000,mgs,osd=osd-ldiskfs"), Some(Mdt));
+    assert_eq!(classify("lustre_tgt", "/dev/sdd", "rw,svname=lustre-OST000a"), Some(Ost));
+    // the kind is the one of the target part, not of the fsname
+    assert_eq!(classify("lustre", "/dev/sde", "rw,svname=my-OSTfs-MDT0000"), Some(Mdt));
+    assert_eq!(classify("lustre", "/dev/sdf", "rw,svname=data-MDT-OST0002"), Some(Ost));
+    assert_eq!(classify("lustre", "/dev/sdg", "rw,svname=pool-OST-MDT0001,mgs"), Some(Mdt));
+    // unknown targets
+    assert_eq!(classify("lustre", "/dev/sdh", "rw,svname=lustre-OSTX"), None);
+    assert_eq!(classify("lustre", "/dev/sdi", "rw,svname=MGS-old"), None);
+    assert_eq!(classify("lustre", "/dev/sdj", "rw,svname=mgs"), None);
+    // not lustre
+    assert_eq!(classify("ext4", "/dev/sda1", "rw,relatime"), None);
+    assert_eq!(classify("nfs", "server:/export/ost", "rw"), None);
+}
+
+#[test]
+fn test_parse_mountinfo_line() {
+    // a client mount with "ost" in its path isn't a server mount
+    assert_eq!(
+        parse_mountinfo_line("120 25 0:52 / /lustre/host_data rw,relatime shared:60 - lustre 10.0.0.1@tcp:/lustre rw,flock,lazystatfs"),
+        Some(LustreMountEntry {
+            id: 120,
+            mount_point: PathBuf::from("/lustre/host_data"),
+            kind: LustreMountKind::Client,
+            svname: None,
+            fsname: Some("lustre".to_string()),
+        }),
+    );
+    assert_eq!(
+        parse_mountinfo_line(r"98 25 8:16 / /srv/my\040target rw,relatime - lustre /dev/sdb ro,svname=lustre-OST0001,osd=osd-ldiskfs"),
+        Some(LustreMountEntry {
+            id: 98,
+            mount_point: PathBuf::from("/srv/my target"),
+            kind: LustreMountKind::Ost,
+            svname: Some("lustre-OST0001".to_string()),
+            fsname: Some("lustre".to_string()),
+        }),
+    );
+    assert_eq!(
+        parse_mountinfo_line("22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw"),
+        None,
+    );
+}
@@ -0,0 +1,131 @@
+use {
+    std::{
+        fs,
+        io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// Where Lustre exposes the state of the local targets.
+///
+/// Depending on the Lustre version, a parameter may be
+/// in either tree.
+pub static DEFAULT_ROOTS: &[&str] = &["/proc/fs/lustre", "/sys/fs/lustre"];
+
+/// Access to the Lustre parameters tree (what `lctl get_param` reads).
+///
+/// The root can be changed, which makes it possible to read
+/// a copy of the tree, for example in tests.
+#[derive(Debug, Clone)]
+pub struct LustreProc {
+    roots: Vec<PathBuf>,
+}
+
+impl Default for LustreProc {
+    fn default() -> Self {
+        Self {
+            roots: DEFAULT_ROOTS.iter().map(PathBuf::from).collect(),
+        }
+    }
+}
+
+impl LustreProc {
+    /// Build an access to a tree with a single root, holding
+    /// all parameters
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { roots: vec![root.into()] }
+    }
+    /// Return the names of the targets of a kind (eg "obdfilter"
+    /// or "mdt"), sorted.
+    ///
+    /// A missing kind directory isn't an error, it just means
+    /// there's no such target on this node.
+    pub fn targets(&self, kind: &str) -> io::Result<Vec<String>> {
+        let mut targets = Vec::new();
+        for root in &self.roots {
+            let entries = match fs::read_dir(root.join(kind)) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    continue;
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+            for entry in entries {
+                let path = entry?.path();
+                if !path.is_dir() {
+                    continue;
+                }
+                if let Some(name) = path.file_name() {
+                    targets.push(name.to_string_lossy().to_string());
+                }
+            }
+        }
+        targets.sort();
+        targets.dedup();
+        Ok(targets)
+    }
+    /// Tell whether there's a directory for this target
+    /// in the given kind (eg "osd-zfs")
+    pub fn has_target(&self, kind: &str, target: &str) -> bool {
+        self.roots.iter().any(|root| root.join(kind).join(target).is_dir())
+    }
+    /// Read a parameter of a target, eg ("obdfilter", "lustre-OST0000", "num_exports").
+    ///
+    /// Return None when the parameter doesn't exist, which is usual
+    /// as it depends on the Lustre version and the enabled features.
+    pub fn read_param(&self, kind: &str, target: &str, param: &str) -> io::Result<Option<String>> {
+        for root in &self.roots {
+            let path = root.join(kind).join(target).join(param);
+            if let Some(content) = read_optional(&path)? {
+                return Ok(Some(content));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Read a file which may legitimately be missing (eg because
+/// the feature isn't enabled or the Lustre version doesn't have it)
+pub fn read_optional(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(s) => Ok(Some(s)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// A parameters tree written in a temporary directory,
+/// removed when dropped
+#[cfg(test)]
+pub struct TestTree {
+    root: PathBuf,
+}
+
+#[cfg(test)]
+impl TestTree {
+    pub fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("dysk-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        Self { root }
+    }
+    pub fn write(&self, path: &str, content: &str) {
+        let path = self.root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    pub fn mkdir(&self, path: &str) {
+        fs::create_dir_all(self.root.join(path)).unwrap();
+    }
+    pub fn proc(&self) -> LustreProc {
+        LustreProc::new(&self.root)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestTree {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,208 @@
+use {
+    std::{
+        fmt,
+        str::FromStr,
+    },
+};
+
+/// The kind of a Lustre target
+///
+/// MDT are ordered before OST.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TargetKind {
+    Mdt,
+    Ost,
+}
+
+impl TargetKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mdt => "MDT",
+            Self::Ost => "OST",
+        }
+    }
+}
+
+impl fmt::Display for TargetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The identity of a MDT or OST, as found in names like
+/// "lustre-OST0001" or "scratch-fs-MDT0000_UUID".
+///
+/// The index is written in hexadecimal in the name, on at least 4 digits.
+///
+/// Names are ordered by filesystem, then kind (MDT before OST), then index.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LustreTargetName {
+    pub fsname: String,
+    pub kind: TargetKind,
+    pub index: u32,
+}
+
+impl LustreTargetName {
+    pub fn new<S: Into<String>>(fsname: S, kind: TargetKind, index: u32) -> Self {
+        Self {
+            fsname: fsname.into(),
+            kind,
+            index,
+        }
+    }
+    /// The UUID of the target, as displayed by `lfs df`
+    pub fn uuid(&self) -> String {
+        format!("{}_UUID", self)
+    }
+}
+
+impl fmt::Display for LustreTargetName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}{:04x}", self.fsname, self.kind, self.index)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseTargetNameError {
+    /// the string which couldn't be parsed
+    pub raw: String,
+}
+impl ParseTargetNameError {
+    pub fn new<S: Into<String>>(s: S) -> Self {
+        Self { raw: s.into() }
+    }
+}
+impl fmt::Display for ParseTargetNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} isn't a Lustre target name like 'lustre-OST0001'", self.raw)
+    }
+}
+impl std::error::Error for ParseTargetNameError {}
+
+impl FromStr for LustreTargetName {
+    type Err = ParseTargetNameError;
+    fn from_str(s: &str) -> Result<Self, ParseTargetNameError> {
+        let name = s.strip_suffix("_UUID").unwrap_or(s);
+        // the fsname may contain dashes, the target part can't
+        let (fsname, target) = name
+            .rsplit_once('-')
+            .ok_or_else(|| ParseTargetNameError::new(s))?;
+        if fsname.is_empty() {
+            return Err(ParseTargetNameError::new(s));
+        }
+        let kind = match target.get(..3) {
+            Some("MDT") => TargetKind::Mdt,
+            Some("OST") => TargetKind::Ost,
+            _ => {
+                return Err(ParseTargetNameError::new(s));
+            }
+        };
+        let hex = &target[3..];
+        if hex.len() < 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseTargetNameError::new(s));
+        }
+        let index = u32::from_str_radix(hex, 16)
+            .map_err(|_| ParseTargetNameError::new(s))?;
+        Ok(Self {
+            fsname: fsname.to_string(),
+            kind,
+            index,
+        })
+    }
+}
+
+#[cfg(test)]
+mod target_name_parsing {
+    use super::{*, TargetKind::*};
+
+    fn check(s: &str, fsname: &str, kind: TargetKind, index: u32) {
+        let name: LustreTargetName = s.parse().unwrap();
+        assert_eq!(name, LustreTargetName::new(fsname, kind, index));
+    }
+
+    #[test]
+    fn valid_names() {
+        check("lustre-MDT0000", "lustre", Mdt, 0);
+        check("lustre-OST0001", "lustre", Ost, 1);
+        check("lustre-OST0001_UUID", "lustre", Ost, 1);
+        check("lustre-OST000a", "lustre", Ost, 10);
+        check("lustre-OST00a1", "lustre", Ost, 0xa1);
+        check("lustre-OST00A1", "lustre", Ost, 0xa1);
+        check("lustre-OST270f", "lustre", Ost, 9999);
+        check("lustre-OSTffff", "lustre", Ost, 0xffff);
+        check("lustre-OST10000", "lustre", Ost, 0x10000);
+        check("scratch-fs-OST0001_UUID", "scratch-fs", Ost, 1);
+        check("a-b-c-MDT0003", "a-b-c", Mdt, 3);
+        check("fs_1-MDT0002_UUID", "fs_1", Mdt, 2);
+    }
+
+    #[test]
+    fn invalid_names() {
+        for s in [
+            "",
+            "filesystem_summary",
+            "lustre",
+            "lustre-",
+            "-OST0001",
+            "lustre-OST",
+            "lustre-OST001",
+            "lustre-OST00g1",
+            "lustre-MGS0000",
+            "lustre-OST0001_uuid",
+            "lustre-OST0001-extra",
+            "/mnt/lustre[OST:1]",
+            "lustre-OST100000000",
+        ] {
+            assert_eq!(
+                s.parse::<LustreTargetName>(),
+                Err(ParseTargetNameError::new(s)),
+                "{s:?} shouldn't be parsed as a target name",
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in [
+            "lustre-MDT0000",
+            "lustre-OST0001",
+            "lustre-OST00a1",
+            "lustre-OSTffff",
+            "lustre-OST10000",
+            "scratch-fs-OST0001",
+            "fs_1-MDT0002",
+        ] {
+            let name: LustreTargetName = s.parse().unwrap();
+            assert_eq!(name.to_string(), s);
+            assert_eq!(name.uuid().parse::<LustreTargetName>().unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        let mut names: Vec<LustreTargetName> = [
+            "lustre-OST000a",
+            "lustre-OST0002",
+            "lustre-MDT0001",
+            "lustre-OST00a1",
+            "lustre-MDT0000",
+            "home-OST0000",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        names.sort();
+        let names: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "home-OST0000",
+                "lustre-MDT0000",
+                "lustre-MDT0001",
+                "lustre-OST0002",
+                "lustre-OST000a",
+                "lustre-OST00a1",
+            ],
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use {
+    crate::{
+        col::Col,
+        mount_row::MountRow,
+        stamp::Stamp,
+        units::Units,
+    },
+};
+
+/// The measurement of a mount (or of a Lustre component) written
+/// in the time-series formats
+struct Measurement {
+    /// the tags, sorted by key
+    tags: Vec<(&'static str, String)>,
+    fields: Vec<(&'static str, u64)>,
+}
+
+impl Measurement {
+    /// None when the mount has no stats
+    fn of(mount: &MountRow, host: &str) -> Option<Self> {
+        let stats = mount.stats()?;
+        // the tags are all texts or integers, so the units don't matter
+        let text = |col: Col| col.value(mount).display(Units::default());
+        // only Lustre filesystems have a name
+        let fsname = mount.lustre.as_ref().and_then(|info| info.fsname.clone());
+        let tags = [
+            ("component_index", text(Col::ComponentIndex)),
+            ("component_type", text(Col::ComponentType)),
+            ("fs_type", text(Col::Type)),
+            ("fsname", fsname),
+            ("host", Some(host.to_string())),
+            ("mount_point", text(Col::MountPoint)),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.filter(|v| !v.is_empty()).map(|v| (key, v)))
+        .collect();
+        let mut fields = vec![
+            ("bytes_used", stats.used()),
+            ("bytes_free", stats.available()),
+            ("bytes_size", stats.size()),
+        ];
+        if let Some(inodes) = &stats.inodes {
+            fields.push(("inodes_used", inodes.used()));
+            fields.push(("inodes_free", inodes.favail));
+            fields.push(("inodes_size", inodes.files));
+        }
+        Some(Self { tags, fields })
+    }
+}
+
+/// The mounts in the InfluxDB line protocol, one `dysk`
+/// measurement per mount, with a nanosecond timestamp
+pub fn influx(mounts: &[&MountRow], stamp: &Stamp) -> String {
+    let mut out = String::new();
+    for measurement in mounts.iter().filter_map(|mount| Measurement::of(mount, &stamp.host)) {
+        out.push_str("dysk");
+        for (key, value) in &measurement.tags {
+            out.push_str(&format!(",{}={}", key, influx_escape(value)));
+        }
+        let fields: Vec<String> = measurement
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{}={}i", key, value))
+            .collect();
+        out.push_str(&format!(" {} {}000000000\n", fields.join(","), stamp.secs));
+    }
+    out
+}
+
+/// The mounts in the Graphite plaintext protocol, one tagged
+/// `dysk.<field>` series per field of each mount
+pub fn graphite(mounts: &[&MountRow], stamp: &Stamp) -> String {
+    let mut out = String::new();
+    for measurement in mounts.iter().filter_map(|mount| Measurement::of(mount, &stamp.host)) {
+        let tags: String = measurement
+            .tags
+            .iter()
+            .map(|(key, value)| format!(";{}={}", key, graphite_escape(value)))
+            .collect();
+        for (key, value) in &measurement.fields {
+            out.push_str(&format!("dysk.{}{} {} {}\n", key, tags, value, stamp.secs));
+        }
+    }
+    out
+}
+
+/// Escape a tag value for the line protocol
+fn influx_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | '=' | ' ' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Replace the characters Graphite doesn't accept in a tag value
+fn graphite_escape(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if matches!(c, ';' | ' ' | '~' | '!' | '^' | '=') { '_' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+fn test_mounts() -> Vec<MountRow> {
+    use {
+        crate::{
+            lustre_target::{LustreTargetName, TargetKind},
+            test_util::mount,
+            LustreInfo,
+        },
+        lfs_core::StatsError,
+    };
+    let mut rows = vec![
+        mount(1, "/srv/metrics-test/my data")
+            .stats(4096, 1000, 250, 200)
+            .inodes(100, 40, 40)
+            .row(),
+        mount(2, "/srv/metrics-test/ost3")
+            .fs("/dev/sdb1")
+            .fs_type("lustre")
+            .stats(1024, 50, 20, 20)
+            .row(),
+        mount(3, "/srv/metrics-test/gone")
+            .fs("server:/export")
+            .fs_type("nfs")
+            .stats_error(StatsError::Unreachable)
+            .row(),
+    ];
+    let mut ost = LustreInfo::new();
+    ost.fsname = Some("scratch".to_string());
+    ost.target = Some(LustreTargetName::new("scratch", TargetKind::Ost, 3));
+    rows[1].lustre = Some(ost);
+    rows
+}
+
+#[cfg(test)]
+fn test_stamp() -> Stamp {
+    Stamp {
+        host: "node12".to_string(),
+        timestamp: "2023-11-14T22:13:20Z".to_string(),
+        secs: 1_700_000_000,
+    }
+}
+
+#[test]
+fn test_influx() {
+    let mounts = test_mounts();
+    let mounts: Vec<&MountRow> = mounts.iter().collect();
+    assert_eq!(
+        influx(&mounts, &test_stamp()),
+        concat!(
+            "dysk,fs_type=ext4,host=node12,mount_point=/srv/metrics-test/my\\ data ",
+            "bytes_used=3072000i,bytes_free=819200i,bytes_size=4096000i,",
+            "inodes_used=60i,inodes_free=40i,inodes_size=100i 1700000000000000000\n",
+            "dysk,component_index=3,component_type=OST,fs_type=lustre,",
+            "fsname=scratch,host=node12,mount_point=/srv/metrics-test/ost3 ",
+            "bytes_used=30720i,bytes_free=20480i,bytes_size=51200i 1700000000000000000\n",
+        ),
+    );
+}
+
+#[test]
+fn test_graphite() {
+    let mounts = test_mounts();
+    let mounts: Vec<&MountRow> = mounts.iter().collect();
+    let tags = ";fs_type=ext4;host=node12;mount_point=/srv/metrics-test/my_data";
+    let lustre_tags = concat!(
+        ";component_index=3;component_type=OST;fs_type=lustre",
+        ";fsname=scratch;host=node12;mount_point=/srv/metrics-test/ost3",
+    );
+    let expected = [
+        format!("dysk.bytes_used{tags} 3072000 1700000000"),
+        format!("dysk.bytes_free{tags} 819200 1700000000"),
+        format!("dysk.bytes_size{tags} 4096000 1700000000"),
+        format!("dysk.inodes_used{tags} 60 1700000000"),
+        format!("dysk.inodes_free{tags} 40 1700000000"),
+        format!("dysk.inodes_size{tags} 100 1700000000"),
+        format!("dysk.bytes_used{lustre_tags} 30720 1700000000"),
+        format!("dysk.bytes_free{lustre_tags} 20480 1700000000"),
+        format!("dysk.bytes_size{lustre_tags} 51200 1700000000"),
+    ];
+    let graphite = graphite(&mounts, &test_stamp());
+    let lines: Vec<&str> = graphite.lines().collect();
+    assert_eq!(lines, expected);
+}
@@ -0,0 +1,41 @@
+use {
+    crate::LustreInfo,
+    lfs_core::Mount,
+    std::{
+        ops::Deref,
+        path::PathBuf,
+    },
+};
+
+/// A row of the filesystems view: a mount, with the mount points
+/// of the mounts merged into it (see `dedup`)
+#[derive(Debug, Clone)]
+pub struct MountRow {
+    pub mount: Mount,
+    /// the mount points of the represented mounts, starting
+    /// with the one of this mount
+    pub mount_points: Vec<PathBuf>,
+    /// read for the Lustre mounts on every listing
+    pub lustre: Option<LustreInfo>,
+}
+
+impl MountRow {
+    /// A row for a mount which represents only itself
+    pub fn new(mount: Mount) -> Self {
+        let mount_points = vec![mount.info.mount_point.clone()];
+        Self { mount, mount_points, lustre: None }
+    }
+}
+
+impl From<Mount> for MountRow {
+    fn from(mount: Mount) -> Self {
+        Self::new(mount)
+    }
+}
+
+impl Deref for MountRow {
+    type Target = Mount;
+    fn deref(&self) -> &Mount {
+        &self.mount
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    cmp::Ordering,
+    iter::Peekable,
+    str::Chars,
+};
+
+/// Compare two strings in "natural" order, where the runs of
+/// digits are compared as numbers, so that `OST2` comes before
+/// `OST10` and `/data2` before `/data10`.
+///
+/// Numbers equal but for leading zeros are ordered by their length
+/// so that the order stays total.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(ca), Some(cb)) if ca.is_ascii_digit() && cb.is_ascii_digit() => {
+                let na = take_digits(&mut a);
+                let nb = take_digits(&mut b);
+                let ordering = cmp_numbers(&na, &nb);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(ca), Some(cb)) => {
+                if ca != cb {
+                    return ca.cmp(&cb);
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Compare two versions, like `2.15.3` and `2.9.0_ddn12`, segment
+/// by segment, a missing numeric segment being taken as 0
+pub fn version_cmp(a: &str, b: &str) -> Ordering {
+    let is_separator = |c: char| matches!(c, '.' | '-' | '_');
+    let mut a = a.split(is_separator);
+    let mut b = b.split(is_separator);
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (Some(sa), None) => natural_cmp(sa, "0"),
+            (None, Some(sb)) => natural_cmp("0", sb),
+            (Some(sa), Some(sb)) => natural_cmp(sa, sb),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn take_digits(chars: &mut Peekable<Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
+
+fn cmp_numbers(a: &str, b: &str) -> Ordering {
+    let ta = a.trim_start_matches('0');
+    let tb = b.trim_start_matches('0');
+    ta.len()
+        .cmp(&tb.len())
+        .then_with(|| ta.cmp(tb))
+        .then_with(|| a.len().cmp(&b.len()))
+}
+
+#[test]
+fn test_natural_cmp() {
+    assert_eq!(natural_cmp("OST2", "OST10"), Ordering::Less);
+    assert_eq!(natural_cmp("/data10", "/data2"), Ordering::Greater);
+    assert_eq!(natural_cmp("/data2/x", "/data2/y"), Ordering::Less);
+    assert_eq!(natural_cmp("a", "a1"), Ordering::Less);
+    assert_eq!(natural_cmp("007", "7"), Ordering::Greater);
+    assert_eq!(natural_cmp("abc", "abc"), Ordering::Equal);
+    let mut names = vec!["sdb10", "sdb2", "sda", "sdb1"];
+    names.sort_by(|a, b| natural_cmp(a, b));
+    assert_eq!(names, vec!["sda", "sdb1", "sdb2", "sdb10"]);
+}
+
+#[test]
+fn test_version_cmp() {
+    assert_eq!(version_cmp("2.9.0", "2.15.3"), Ordering::Less);
+    assert_eq!(version_cmp("2.15", "2.15.0"), Ordering::Equal);
+    assert_eq!(version_cmp("2.15.3", "2.15"), Ordering::Greater);
+    assert_eq!(version_cmp("2.14.0_ddn12", "2.14.0_ddn9"), Ordering::Greater);
+    assert_eq!(version_cmp("2.12.9", "2.12.9"), Ordering::Equal);
+}
@@ -0,0 +1,303 @@
+use {
+    crate::{
+        col::TableCol,
+        col_expr::{parse_float, parse_integer, ColOperator},
+    },
+};
+
+/// The kind of a numeric value, checked at parse time
+/// when values are combined or compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumKind {
+    /// a number of bytes or of items, eg `size` or `32G`
+    Amount,
+    /// a ratio, eg `use` or `20%`
+    Share,
+    /// a number without unit, eg `2`, which goes with any kind
+    Scalar,
+}
+
+impl NumKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Amount => "an amount",
+            Self::Share => "a percentage",
+            Self::Scalar => "a number",
+        }
+    }
+    fn is_compatible(self, other: Self) -> bool {
+        self == other || self == Self::Scalar || other == Self::Scalar
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// An arithmetic expression on the numeric columns of a row,
+/// eg `size*5%` or `use_percent+20%`
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumExpr<C> {
+    Literal {
+        value: f64,
+        kind: NumKind,
+    },
+    Col(C),
+    Binary {
+        left: Box<NumExpr<C>>,
+        operator: ArithOperator,
+        right: Box<NumExpr<C>>,
+    },
+}
+
+/// Tell whether a side of a comparison is more than a single operand
+pub fn has_arith_operator(s: &str) -> bool {
+    s.contains(['+', '-', '*', '/'])
+}
+
+impl<C: TableCol> NumExpr<C> {
+    /// Tell whether the expression refers to the column
+    pub fn uses(&self, col: C) -> bool {
+        match self {
+            Self::Literal { .. } => false,
+            Self::Col(c) => *c == col,
+            Self::Binary { left, right, .. } => left.uses(col) || right.uses(col),
+        }
+    }
+    /// Parse an expression made of columns, literals, and the
+    /// `+`, `-`, `*`, `/` operators (`*` and `/` first).
+    ///
+    /// Errors come with the offset of the offending operand.
+    pub fn parse(s: &str) -> Result<Self, (usize, String)> {
+        let mut operands = Vec::new();
+        let mut operators = Vec::new();
+        let mut start = 0;
+        for (idx, c) in s.char_indices() {
+            let operator = match c {
+                '+' => ArithOperator::Add,
+                '-' => ArithOperator::Sub,
+                '*' => ArithOperator::Mul,
+                '/' => ArithOperator::Div,
+                _ => {
+                    continue;
+                }
+            };
+            operands.push(Self::parse_operand(&s[start..idx], start)?);
+            operators.push(operator);
+            start = idx + 1;
+        }
+        operands.push(Self::parse_operand(&s[start..], start)?);
+        // multiplications and divisions are applied first
+        let mut operands = operands.into_iter();
+        let mut terms = operands.next().into_iter().collect::<Vec<_>>();
+        let mut additions = Vec::new();
+        for (operator, operand) in operators.into_iter().zip(operands) {
+            match operator {
+                ArithOperator::Mul | ArithOperator::Div => {
+                    let left = terms.pop().unwrap();
+                    terms.push(Self::Binary {
+                        left: Box::new(left),
+                        operator,
+                        right: Box::new(operand),
+                    });
+                }
+                _ => {
+                    additions.push(operator);
+                    terms.push(operand);
+                }
+            }
+        }
+        let mut terms = terms.into_iter();
+        let mut expr = terms.next().unwrap();
+        for (operator, term) in additions.into_iter().zip(terms) {
+            expr = Self::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(term),
+            };
+        }
+        Ok(expr)
+    }
+    fn parse_operand(s: &str, offset: usize) -> Result<Self, (usize, String)> {
+        let Some(first) = s.chars().next() else {
+            return Err((offset, "missing operand".to_string()));
+        };
+        if first.is_ascii_digit() || first == '.' {
+            let (value, kind) = if s.ends_with('%') {
+                (parse_float(s).map_err(|e| (offset, e.to_string()))?, NumKind::Share)
+            } else if let Ok(value) = s.parse::<f64>() {
+                (value, NumKind::Scalar)
+            } else {
+                let value = parse_integer(s).map_err(|e| (offset, e.to_string()))?;
+                (value as f64, NumKind::Amount)
+            };
+            return Ok(Self::Literal { value, kind });
+        }
+        let col: C = s.parse().map_err(|e: crate::col::ParseColError| (offset, e.to_string()))?;
+        if col.num_kind().is_none() {
+            return Err((offset, format!("{:?} isn't a numeric column", s)));
+        }
+        Ok(Self::Col(col))
+    }
+    /// Check the expression combines compatible values, and return
+    /// the kind of its result
+    pub fn kind(&self) -> Result<NumKind, String> {
+        use NumKind::*;
+        match self {
+            Self::Literal { kind, .. } => Ok(*kind),
+            Self::Col(col) => Ok(col.num_kind().unwrap_or(Scalar)),
+            Self::Binary { left, operator, right } => {
+                let (l, r) = (left.kind()?, right.kind()?);
+                match operator {
+                    ArithOperator::Add | ArithOperator::Sub => {
+                        if l.is_compatible(r) {
+                            Ok(if l == Scalar { r } else { l })
+                        } else {
+                            Err(format!("can't add or subtract {} and {}", l.name(), r.name()))
+                        }
+                    }
+                    ArithOperator::Mul => match (l, r) {
+                        (Amount, Amount) => Err("can't multiply two amounts".to_string()),
+                        (Scalar, k) | (k, Scalar) => Ok(k),
+                        (Share, k) | (k, Share) => Ok(k),
+                    },
+                    ArithOperator::Div => match (l, r) {
+                        (k, Scalar) => Ok(k),
+                        (Amount, Amount) => Ok(Share),
+                        (Amount, Share) => Ok(Amount),
+                        (Share, Share) => Ok(Scalar),
+                        (_, _) => Err(format!("can't divide {} by {}", l.name(), r.name())),
+                    },
+                }
+            }
+        }
+    }
+    /// The first column referenced in the expression
+    pub fn first_col(&self) -> Option<C> {
+        match self {
+            Self::Literal { .. } => None,
+            Self::Col(col) => Some(*col),
+            Self::Binary { left, right, .. } => left.first_col().or_else(|| right.first_col()),
+        }
+    }
+    /// Compute the value of the expression for a row, None when a
+    /// value is missing or on a division by zero
+    pub fn eval(&self, row: &C::Row) -> Option<f64> {
+        match self {
+            Self::Literal { value, .. } => Some(*value),
+            Self::Col(col) => col.number(row),
+            Self::Binary { left, operator, right } => {
+                let (l, r) = (left.eval(row)?, right.eval(row)?);
+                match operator {
+                    ArithOperator::Add => Some(l + r),
+                    ArithOperator::Sub => Some(l - r),
+                    ArithOperator::Mul => Some(l * r),
+                    ArithOperator::Div if r == 0.0 => None,
+                    ArithOperator::Div => Some(l / r),
+                }
+            }
+        }
+    }
+}
+
+/// A comparison between two numeric expressions, eg `free < size*5%`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumComparison<C> {
+    pub left: NumExpr<C>,
+    pub right: NumExpr<C>,
+}
+
+impl<C: TableCol> NumComparison<C> {
+    /// Check the two sides can be compared
+    pub fn check(&self) -> Result<(), String> {
+        let (l, r) = (self.left.kind()?, self.right.kind()?);
+        if l.is_compatible(r) {
+            Ok(())
+        } else {
+            Err(format!("can't compare {} with {}", l.name(), r.name()))
+        }
+    }
+    /// Evaluate the comparison, which is false when a value is missing
+    pub fn eval(&self, operator: ColOperator, row: &C::Row) -> bool {
+        match (self.left.eval(row), self.right.eval(row)) {
+            (Some(l), Some(r)) => operator.eval(l, r),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod num_expr_parsing {
+    use {
+        super::{*, ArithOperator::*, NumKind::*},
+        crate::col::Col,
+    };
+
+    fn parse(s: &str) -> NumExpr<Col> {
+        NumExpr::parse(s).unwrap()
+    }
+    fn lit(value: f64, kind: NumKind) -> Box<NumExpr<Col>> {
+        Box::new(NumExpr::Literal { value, kind })
+    }
+    fn col(col: Col) -> Box<NumExpr<Col>> {
+        Box::new(NumExpr::Col(col))
+    }
+
+    #[test]
+    fn operands() {
+        assert_eq!(parse("size"), NumExpr::Col(Col::Size));
+        assert_eq!(parse("32G"), *lit(32e9, Amount));
+        assert_eq!(parse("20%"), *lit(0.2, Share));
+        assert_eq!(parse("2"), *lit(2.0, Scalar));
+        assert_eq!(NumExpr::<Col>::parse("type").unwrap_err(), (0, "\"type\" isn't a numeric column".to_string()));
+        assert_eq!(NumExpr::<Col>::parse("size+").unwrap_err(), (5, "missing operand".to_string()));
+        assert!(NumExpr::<Col>::parse("size*siz").is_err());
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("used+size*5%"),
+            NumExpr::Binary {
+                left: col(Col::Used),
+                operator: Add,
+                right: Box::new(NumExpr::Binary {
+                    left: col(Col::Size),
+                    operator: Mul,
+                    right: lit(0.05, Share),
+                }),
+            },
+        );
+        assert_eq!(
+            parse("size-used-free"),
+            NumExpr::Binary {
+                left: Box::new(NumExpr::Binary {
+                    left: col(Col::Size),
+                    operator: Sub,
+                    right: col(Col::Used),
+                }),
+                operator: Sub,
+                right: col(Col::Free),
+            },
+        );
+    }
+
+    #[test]
+    fn kinds() {
+        assert_eq!(parse("size*5%").kind(), Ok(Amount));
+        assert_eq!(parse("use_percent+20%").kind(), Ok(Share));
+        assert_eq!(parse("used/size").kind(), Ok(Share));
+        assert_eq!(parse("free*2").kind(), Ok(Amount));
+        assert!(parse("size+20%").kind().is_err());
+        assert!(parse("size*free").kind().is_err());
+        let comparison = NumComparison {
+            left: parse("free"),
+            right: parse("use"),
+        };
+        assert_eq!(comparison.check(), Err("can't compare an amount with a percentage".to_string()));
+    }
+}
@@ -25,7 +25,7 @@ impl ParseOrderError {
 }
 impl fmt::Display for ParseOrderError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?} can't be parsed as a sort order. Use 'asc' or 'desc' (or nothing)", self.raw)
+        write!(f, "{:?} can't be parsed as a sort order. Use 'asc', 'desc', or 'natural' (or nothing)", self.raw)
     }
 }
 impl std::error::Error for ParseOrderError {}
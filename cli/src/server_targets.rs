@@ -0,0 +1,221 @@
+use {
+    crate::{
+        lustre_proc::LustreProc,
+        lustre_target::{LustreTargetName, TargetKind},
+    },
+    std::{
+        io,
+        path::PathBuf,
+    },
+};
+
+/// The kinds of local targets, with the directory holding their parameters
+static SERVER_KINDS: &[(&str, TargetKind)] = &[("mdt", TargetKind::Mdt), ("obdfilter", TargetKind::Ost)];
+
+/// The OSD (object storage device) a target may be backed by
+static BACKENDS: &[(&str, &str)] = &[("osd-ldiskfs", "ldiskfs"), ("osd-zfs", "zfs")];
+
+/// The state of the recovery of a target, as found
+/// in its recovery_status
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recovery {
+    /// eg "COMPLETE", "RECOVERING", "INACTIVE"
+    pub status: String,
+    pub completed_clients: Option<u32>,
+    pub total_clients: Option<u32>,
+}
+
+/// A MDT or OST served by this node
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerTarget {
+    /// eg "lustre-OST0002"
+    pub name: LustreTargetName,
+    /// the block device or ZFS dataset
+    pub device: Option<String>,
+    /// "ldiskfs" or "zfs"
+    pub backend: Option<&'static str>,
+    pub recovery: Option<Recovery>,
+    /// number of connected exports (clients and other targets)
+    pub exports: Option<u64>,
+    pub read_only: Option<bool>,
+    pub size: Option<u64>,
+    pub available: Option<u64>,
+    pub mount_point: Option<PathBuf>,
+}
+
+/// Read the MDT and OST served by this node.
+///
+/// The list is empty on a node which is only a client. Targets
+/// are sorted by filesystem, kind, then index.
+pub fn read_server_targets(proc: &LustreProc) -> io::Result<Vec<ServerTarget>> {
+    let mut targets = Vec::new();
+    for &(kind_dir, kind) in SERVER_KINDS {
+        for name in proc.targets(kind_dir)? {
+            // other entries, like "num_refs", aren't targets
+            let Some(target_name) = name
+                .parse::<LustreTargetName>()
+                .ok()
+                .filter(|target_name| target_name.kind == kind)
+            else {
+                continue;
+            };
+            let osd_dir = BACKENDS
+                .iter()
+                .find(|(osd_dir, _)| proc.has_target(osd_dir, &name));
+            // parameters are read in the OSD directory first, then in the target one
+            let mut dirs = Vec::new();
+            if let Some((osd_dir, _)) = osd_dir {
+                dirs.push(*osd_dir);
+            }
+            dirs.push(kind_dir);
+            // a parameter which can't be read (eg not readable by
+            // the current user) is just a missing value
+            let read = |params: &[&str]| -> Option<String> {
+                for dir in &dirs {
+                    for param in params {
+                        if let Ok(Some(value)) = proc.read_param(dir, &name, param) {
+                            return Some(value.trim().to_string());
+                        }
+                    }
+                }
+                None
+            };
+            let device = read(&["mntdev"]);
+            let recovery = read(&["recovery_status"])
+                .map(|content| parse_recovery_status(&content));
+            let exports = read(&["num_exports"])
+                .and_then(|s| s.parse().ok());
+            let read_only = read(&["read_only", "readonly"])
+                .map(|s| s != "0");
+            let size = read(&["kbytestotal"])
+                .and_then(|s| s.parse::<u64>().ok())
+                .map(|kb| kb * 1024);
+            let available = read(&["kbytesavail"])
+                .and_then(|s| s.parse::<u64>().ok())
+                .map(|kb| kb * 1024);
+            targets.push(ServerTarget {
+                name: target_name,
+                device,
+                backend: osd_dir.map(|(_, backend)| *backend),
+                recovery,
+                exports,
+                read_only,
+                size,
+                available,
+                mount_point: None,
+            });
+        }
+    }
+    targets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(targets)
+}
+
+/// Parse the content of a recovery_status file, which looks like
+///
+/// ```yaml
+/// status: COMPLETE
+/// recovery_start: 1700000000
+/// recovery_duration: 35
+/// completed_clients: 10/12
+/// replayed_requests: 0
+/// ```
+pub fn parse_recovery_status(content: &str) -> Recovery {
+    let mut recovery = Recovery {
+        status: String::new(),
+        completed_clients: None,
+        total_clients: None,
+    };
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "status" => {
+                recovery.status = value.to_string();
+            }
+            "completed_clients" => {
+                if let Some((completed, total)) = value.split_once('/') {
+                    recovery.completed_clients = completed.trim().parse().ok();
+                    recovery.total_clients = total.trim().parse().ok();
+                }
+            }
+            _ => {}
+        }
+    }
+    recovery
+}
+
+#[test]
+fn test_parse_recovery_status() {
+    assert_eq!(
+        parse_recovery_status(
+            "status: COMPLETE\nrecovery_start: 1700000000\nrecovery_duration: 35\ncompleted_clients: 10/12\nreplayed_requests: 0\n"
+        ),
+        Recovery {
+            status: "COMPLETE".to_string(),
+            completed_clients: Some(10),
+            total_clients: Some(12),
+        },
+    );
+    assert_eq!(
+        parse_recovery_status("status: INACTIVE\n"),
+        Recovery {
+            status: "INACTIVE".to_string(),
+            completed_clients: None,
+            total_clients: None,
+        },
+    );
+}
+
+#[test]
+fn test_read_server_targets() {
+    use crate::lustre_proc::TestTree;
+    let tree = TestTree::new("server");
+    tree.write("obdfilter/scratch-OST0001/recovery_status", "status: RECOVERING\ncompleted_clients: 3/8\n");
+    tree.write("obdfilter/scratch-OST0001/num_exports", "9\n");
+    tree.write("osd-zfs/scratch-OST0001/mntdev", "ostpool/ost1\n");
+    tree.write("osd-zfs/scratch-OST0001/kbytestotal", "1000\n");
+    tree.write("osd-zfs/scratch-OST0001/kbytesavail", "250\n");
+    tree.write("mdt/scratch-MDT0000/recovery_status", "status: COMPLETE\ncompleted_clients: 8/8\n");
+    tree.write("osd-ldiskfs/scratch-MDT0000/mntdev", "/dev/sdb\n");
+    tree.write("osd-ldiskfs/scratch-MDT0000/read_only", "1\n");
+    // an unreadable parameter doesn't prevent reading the others
+    tree.mkdir("mdt/scratch-MDT0000/num_exports");
+    let targets = read_server_targets(&tree.proc()).unwrap();
+    assert_eq!(
+        targets,
+        vec![
+            ServerTarget {
+                name: LustreTargetName::new("scratch", TargetKind::Mdt, 0),
+                device: Some("/dev/sdb".to_string()),
+                backend: Some("ldiskfs"),
+                recovery: Some(Recovery {
+                    status: "COMPLETE".to_string(),
+                    completed_clients: Some(8),
+                    total_clients: Some(8),
+                }),
+                exports: None,
+                read_only: Some(true),
+                size: None,
+                available: None,
+                mount_point: None,
+            },
+            ServerTarget {
+                name: LustreTargetName::new("scratch", TargetKind::Ost, 1),
+                device: Some("ostpool/ost1".to_string()),
+                backend: Some("zfs"),
+                recovery: Some(Recovery {
+                    status: "RECOVERING".to_string(),
+                    completed_clients: Some(3),
+                    total_clients: Some(8),
+                }),
+                exports: Some(9),
+                read_only: None,
+                size: Some(1024000),
+                available: Some(256000),
+                mount_point: None,
+            },
+        ],
+    );
+}
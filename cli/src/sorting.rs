@@ -1,38 +1,114 @@
 use {
     crate::{
-        col::Col,
+        col::{Col, TableCol},
         order::Order,
     },
-    lfs_core::Mount,
     std::{
+        cmp::Ordering,
         error,
         fmt,
         str::FromStr,
     },
 };
 
-/// Sorting directive: the column and the order (asc or desc)
+/// A sort key: the column, the order (asc or desc), and whether
+/// texts are compared in natural order (`OST2` before `OST10`)
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Sorting {
-    col: Col,
-    order: Order,
+pub struct SortKey<C = Col> {
+    pub col: C,
+    pub order: Order,
+    pub natural: bool,
 }
 
-impl Default for Sorting {
+impl<C: TableCol> SortKey<C> {
+    /// Compare two rows on this key. Rows without value come
+    /// last, whatever the order
+    pub fn compare(self, a: &C::Row, b: &C::Row) -> Ordering {
+        let (a, b) = (self.col.value(a), self.col.value(b));
+        match (a.is_missing(), b.is_missing()) {
+            (false, false) => {
+                let ordering = if self.natural {
+                    a.compare_natural(&b)
+                } else {
+                    a.compare(&b)
+                };
+                match self.order {
+                    Order::Asc => ordering,
+                    Order::Desc => ordering.reverse(),
+                }
+            }
+            (a_missing, b_missing) => a_missing.cmp(&b_missing),
+        }
+    }
+}
+
+impl<C: TableCol> FromStr for SortKey<C> {
+    type Err = ParseSortingError;
+    /// Parse a column followed by modifiers, eg `size`, `size-asc`,
+    /// `fs-natural`, or `mount-natural-desc`
+    fn from_str(s: &str) -> Result<Self, ParseSortingError> {
+        let mut parts = s.split(|c: char| c.is_whitespace() || c == '-');
+        let s_col = parts.next().unwrap_or_default();
+        let col: C = s_col.parse()
+            .map_err(|pce| ParseSortingError::new(s, Box::new(pce)))?;
+        let mut order = None;
+        let mut natural = false;
+        for part in parts {
+            if part.eq_ignore_ascii_case("natural") {
+                natural = true;
+            } else {
+                let o: Order = part.parse()
+                    .map_err(|poe| ParseSortingError::new(s, Box::new(poe)))?;
+                order = Some(o);
+            }
+        }
+        let order = order.unwrap_or_else(|| col.default_sort_order());
+        Ok(Self { col, order, natural })
+    }
+}
+
+/// Sorting directive: the keys, each one only deciding
+/// between the rows the previous ones find equal
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sorting<C = Col> {
+    keys: Vec<SortKey<C>>,
+}
+
+impl<C: TableCol> Default for Sorting<C> {
     fn default() -> Self {
-        let col = Col::default_sort_col();
+        let col = C::default_sort_col();
         let order = col.default_sort_order();
-        Self { col, order }
+        Self {
+            keys: vec![SortKey { col, order, natural: false }],
+        }
     }
 }
 
-impl Sorting {
-    pub fn sort(self, mounts: &mut [Mount]) {
-        let comparator = self.col.comparator();
-        mounts.sort_by(comparator);
-        if self.order == Order::Desc {
-            mounts.reverse();
-        }
+impl Sorting<Col> {
+    /// The sorting of Lustre views: the MDTs, then the OSTs,
+    /// then the client mounts
+    pub fn lustre() -> Self {
+        LUSTRE_SORTING.parse().unwrap()
+    }
+}
+
+static LUSTRE_SORTING: &str = "target,fs,mount";
+
+impl<C: TableCol> Sorting<C> {
+    pub fn keys(&self) -> &[SortKey<C>] {
+        &self.keys
+    }
+    pub fn compare(&self, a: &C::Row, b: &C::Row) -> Ordering {
+        self.keys
+            .iter()
+            .map(|key| key.compare(a, b))
+            .find(|&ordering| ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+    /// Sort the rows, keeping the order of the rows which are
+    /// equal on all keys
+    pub fn sort(&self, rows: &mut [C::Row]) {
+        rows.sort_by(|a, b| self.compare(a, b));
     }
 }
 
@@ -56,28 +132,101 @@ impl fmt::Display for ParseSortingError {
 }
 impl error::Error for ParseSortingError {}
 
-impl FromStr for Sorting {
+impl<C: TableCol> FromStr for Sorting<C> {
     type Err = ParseSortingError;
+    /// Parse a comma separated list of keys, eg `type,use-desc,mount`
     fn from_str(s: &str) -> Result<Self, ParseSortingError> {
-        let cut_idx_len = s
-            .char_indices()
-            .find(|(_idx, c)| c.is_whitespace() || *c == '-')
-            .map(|(idx, c)| (idx, c.len_utf8()));
-        let (s_col, s_order) = match cut_idx_len {
-            Some((idx, len)) => (&s[..idx], Some(&s[idx+len..])),
-            None => (s, None),
-        };
-        let col: Col = s_col.parse()
-            .map_err(|pce| ParseSortingError::new(s, Box::new(pce)))?;
-        let order = match s_order {
-            Some(s_order) => {
-                s_order.parse()
-                    .map_err(|poe| ParseSortingError::new(s, Box::new(poe)))?
-            }
-            None => {
-                col.default_sort_order()
-            }
-        };
-        Ok(Self { col, order })
+        let keys = s
+            .split(',')
+            .map(|key| {
+                let key = key.trim();
+                if key.is_empty() {
+                    Err(ParseSortingError::new(s, "a sort key is empty"))
+                } else {
+                    key.parse()
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { keys })
+    }
+}
+
+#[cfg(test)]
+mod sorting_parsing {
+    use {
+        super::*,
+        crate::{
+            job_col::JobCol,
+            jobstats::JobStat,
+            test_util,
+        },
+    };
+
+    fn job(job_id: &str, target: &str, read_bytes: u64, write_bytes: u64) -> JobStat {
+        JobStat {
+            read_bytes,
+            write_bytes,
+            ..test_util::job(job_id, target)
+        }
+    }
+
+    #[test]
+    fn parse_keys() {
+        let sorting: Sorting = "type,use-desc, mount".parse().unwrap();
+        let key = |col, order, natural| SortKey { col, order, natural };
+        assert_eq!(
+            sorting.keys(),
+            &[
+                key(Col::Type, Order::Asc, false),
+                key(Col::Use, Order::Desc, false),
+                key(Col::MountPoint, Order::Asc, false),
+            ],
+        );
+        let sorting: Sorting = "size".parse().unwrap();
+        assert_eq!(sorting.keys(), &[key(Col::Size, Order::Desc, false)]);
+        let sorting: Sorting = "fs-natural,mount-natural-desc,type-d-natural".parse().unwrap();
+        assert_eq!(
+            sorting.keys(),
+            &[
+                key(Col::Filesystem, Order::Asc, true),
+                key(Col::MountPoint, Order::Desc, true),
+                key(Col::Type, Order::Desc, true),
+            ],
+        );
+        assert!("type,,mount".parse::<Sorting>().is_err());
+        assert!("type,nope".parse::<Sorting>().is_err());
+        assert!("type-up".parse::<Sorting>().is_err());
+    }
+
+    #[test]
+    fn sort_on_several_keys() {
+        let mut jobs = vec![
+            job("a", "OST0001", 10, 5),
+            job("b", "OST0000", 10, 7),
+            job("c", "OST0001", 20, 5),
+            job("d", "OST0000", 10, 7),
+            job("e", "OST0000", 30, 1),
+        ];
+        let sorting: Sorting<JobCol> = "target,write-desc,read-asc".parse().unwrap();
+        sorting.sort(&mut jobs);
+        let ids: Vec<&str> = jobs.iter().map(|j| j.job_id.as_str()).collect();
+        // b and d are equal on all keys and keep their order
+        assert_eq!(ids, vec!["b", "d", "e", "a", "c"]);
+    }
+
+    #[test]
+    fn natural_sort() {
+        let mut jobs = vec![
+            job("a", "OST10", 0, 0),
+            job("b", "OST2", 0, 0),
+            job("c", "OST1", 0, 0),
+        ];
+        let ids = |jobs: &[JobStat]| jobs.iter().map(|j| j.job_id.clone()).collect::<Vec<_>>();
+        "target".parse::<Sorting<JobCol>>().unwrap().sort(&mut jobs);
+        assert_eq!(ids(&jobs), vec!["c", "a", "b"]);
+        "target-natural".parse::<Sorting<JobCol>>().unwrap().sort(&mut jobs);
+        assert_eq!(ids(&jobs), vec!["c", "b", "a"]);
+        "target-natural-desc".parse::<Sorting<JobCol>>().unwrap().sort(&mut jobs);
+        assert_eq!(ids(&jobs), vec!["a", "b", "c"]);
     }
 }
@@ -0,0 +1,82 @@
+use {
+    serde_json::{json, Value},
+    std::{
+        ffi::CStr,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Where and when records are produced, added to each record
+/// of a stream so that they make sense out of their context
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stamp {
+    pub host: String,
+    /// RFC 3339 UTC time, eg `2025-03-01T18:04:12Z`
+    pub timestamp: String,
+    /// the same time, in seconds since the epoch
+    pub secs: u64,
+}
+
+impl Stamp {
+    pub fn now() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            host: hostname(),
+            timestamp: rfc3339(secs),
+            secs,
+        }
+    }
+    /// Add the host and the timestamp to a record, when it's an object
+    pub fn apply(&self, mut record: Value) -> Value {
+        if let Value::Object(map) = &mut record {
+            map.insert("host".to_string(), json!(self.host));
+            map.insert("timestamp".to_string(), json!(self.timestamp));
+        }
+        record
+    }
+}
+
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    let ret = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    if ret != 0 {
+        return "unknown".to_string();
+    }
+    CStr::from_bytes_until_nul(&buf)
+        .map_or_else(|_| "unknown".to_string(), |name| name.to_string_lossy().to_string())
+}
+
+/// Format a number of seconds since the epoch as an UTC RFC 3339 time
+fn rfc3339(secs: u64) -> String {
+    let (days, secs) = ((secs / 86_400) as i64, secs % 86_400);
+    // conversion of a day number to a date, from
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+    )
+}
+
+#[test]
+fn test_rfc3339() {
+    assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+    assert_eq!(rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(rfc3339(1_700_000_000), "2023-11-14T22:13:20Z");
+    assert_eq!(rfc3339(4_102_444_799), "2099-12-31T23:59:59Z");
+}
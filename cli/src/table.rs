@@ -1,11 +1,17 @@
 use {
     crate::{
-        Args, col::Col, get_lustre_info,
+        Args, col::Col,
+        changelog::{self, ChangelogUser},
+        col_value::ColValue,
+        job_col::{JobCol, ALL_JOB_COLS},
+        jobstats::JobStat,
+        mount_row::MountRow,
+        server_targets::ServerTarget,
+        totals::Totals,
     },
-    lfs_core::*,
     termimad::{
         crossterm::style::Color::*,
-        minimad::{self, OwningTemplateExpander, TableBuilder},
+        minimad::{self, Alignment, OwningTemplateExpander, OwningSubTemplateExpander, TableBuilder},
         CompoundStyle, MadSkin, ProgressBar,
     },
 };
@@ -20,44 +26,50 @@ static SIZE_COLOR: u8 = 172;
 static BAR_WIDTH: usize = 5;
 static INODES_BAR_WIDTH: usize = 5;
 
-/// Format stripe size for display (e.g., 1048576 -> "1M")
-fn format_stripe_size(size: u64) -> String {
-    if size == 0 {
-        return "0".to_string();
-    }
-    
-    // Handle unreasonably large values
-    if size > 1024 * 1024 * 1024 * 1024 {  // > 1TB
-        return "-".to_string();
-    }
-    
-    const UNITS: &[(&str, u64)] = &[
-        ("G", 1024 * 1024 * 1024),
-        ("M", 1024 * 1024),
-        ("K", 1024),
-    ];
-    
-    for (unit, divisor) in UNITS {
-        if size >= *divisor && size % divisor == 0 {
-            return format!("{}{}", size / divisor, unit);
+/// Set the content of a cell, and of its bar for the usage columns.
+///
+/// The expander keys are the column names
+fn set_cell(sub: &mut OwningSubTemplateExpander<'static>, col: Col, value: &ColValue, args: &Args) {
+    let key = col.name();
+    match (col, value) {
+        (_, ColValue::Missing) => {}
+        (Col::Use | Col::InodesUse, ColValue::Float(share)) => {
+            let (bar_key, width) = if col == Col::Use {
+                ("use-bar", BAR_WIDTH)
+            } else {
+                ("inodes-bar", INODES_BAR_WIDTH)
+            };
+            sub.set(key, format!("{:>3.0}%", 100.0 * share));
+            sub.set_md(bar_key, progress_bar_md(*share, width, args.ascii));
         }
-    }
-    
-    // For values that don't divide evenly, show with appropriate unit anyway
-    for (unit, divisor) in UNITS {
-        if size >= *divisor {
-            return format!("{:.1}{}", size as f64 / *divisor as f64, unit);
+        (_, ColValue::Float(share)) => {
+            sub.set(key, format!("{:>3.0}%", 100.0 * share));
+        }
+        (_, value) => {
+            if let Some(s) = value.display(args.units) {
+                sub.set(key, s);
+            }
         }
     }
-    
-    size.to_string()
 }
 
-pub fn print(mounts: &[&Mount], color: bool, args: &Args) {
+/// The template of a column, whose style depends on the kind of value
+fn cell_template(col: Col) -> String {
+    let key = col.name();
+    match col {
+        Col::Use => "~~${use}~~ ${use-bar}~~${use-error}~~".to_string(),
+        Col::InodesUse => "~~${inodes}~~ ${inodes-bar}".to_string(),
+        Col::Used | Col::UsePercent | Col::InodesUsed | Col::InodesUsePercent => format!("~~${{{key}}}~~"),
+        Col::Free | Col::FreePercent | Col::InodesFree => format!("*${{{key}}}*"),
+        Col::Size | Col::InodesCount => format!("**${{{key}}}**"),
+        _ => format!("${{{key}}}"),
+    }
+}
+
+pub fn print(mounts: &[&MountRow], color: bool, args: &Args) {
     if args.cols.is_empty() {
         return;
     }
-    let units = args.units;
     let inodes_mode = args.inodes;  // Add this line
     let mut expander = OwningTemplateExpander::new();
     expander.set_default("");
@@ -73,156 +85,188 @@ pub fn print(mounts: &[&Mount], color: bool, args: &Args) {
             added_separator = true;
         }
 
-        let sub = expander
-            .sub("rows")
-            .set("id", mount.info.id)
-            .set("dev-major", mount.info.dev.major)
-            .set("dev-minor", mount.info.dev.minor)
-            .set("filesystem", &mount.info.fs)
-            .set("disk", mount.disk.as_ref().map_or("", |d| d.disk_type()))
-            .set("type", &mount.info.fs_type)
-            .set("mount-point", mount.info.mount_point.to_string_lossy())
-            .set("fs-name", crate::col::extract_fsname(&mount))
-            .set_option("uuid", mount.uuid.as_ref())
-            .set_option("part_uuid", mount.part_uuid.as_ref());
-
-        // Add Lustre-specific information
-        let mount_point_str = mount.info.mount_point.to_string_lossy();
-        if let Some(lustre_info) = get_lustre_info(&mount_point_str) {
-            if let Some(stripe_count) = lustre_info.stripe_count {
-                // Handle unreasonably large stripe counts (likely uninitialized values)
-                if stripe_count > 1000 {
-                    sub.set("stripe-count", "-");
-                } else {
-                    sub.set("stripe-count", stripe_count);
-                }
-            }
-            if let Some(stripe_size) = lustre_info.stripe_size {
-                sub.set("stripe-size", format_stripe_size(stripe_size));
-            }
-            if let Some(lustre_version) = lustre_info.lustre_version {
-                sub.set("lustre-version", lustre_version);
-            }
-            if let Some(pool_name) = lustre_info.pool_name {
-                if pool_name.is_empty() {
-                    sub.set("pool-name", "default");
-                } else {
-                    sub.set("pool-name", pool_name);
-                }
-            }
-            if let Some(component_type) = lustre_info.component_type {
-                sub.set("component-type", component_type);
-            }
-            if let Some(component_index) = lustre_info.component_index {
-                sub.set("component-index", component_index);
-            }
-            if let Some(mirror_count) = lustre_info.mirror_count {
-                sub.set("mirror-count", mirror_count);
-            }
-        }
-        if let Some(label) = &mount.fs_label {
-            sub.set("label", label);
-        }
-        if mount.info.is_remote() {
-            sub.set("remote", "x");
+        let sub = expander.sub("rows");
+        for &col in args.cols.cols() {
+            set_cell(sub, col, &col.displayed_value(mount, inodes_mode), args);
         }
         if let Some(stats) = mount.stats() {
-            if inodes_mode {
-                // Show inode data instead of byte data
-                if let Some(inodes) = &stats.inodes {
-                    let iuse_share = inodes.use_share();
-                    let ifree_share = 1.0 - iuse_share;
-                    sub
-                        .set("size", inodes.files)
-                        .set("used", inodes.used())
-                        .set("use-percents", format!("{:>3.0}%", 100.0 * iuse_share))
-                        .set_md("bar", progress_bar_md(iuse_share, BAR_WIDTH, args.ascii))
-                        .set("free", inodes.favail)
-                        .set("free-percents", format!("{:>3.0}%", 100.0 * ifree_share));
-                } else {
-                    sub.set("use-error", "no inodes data");
-                }
-            } else {
-                // Show byte data (default)
-                let use_share = stats.use_share();
-                let free_share = 1.0 - use_share;
-                sub
-                    .set("size", units.fmt(stats.size()))
-                    .set("used", units.fmt(stats.used()))
-                    .set("use-percents", format!("{:>3.0}%", 100.0 * use_share))
-                    .set_md("bar", progress_bar_md(use_share, BAR_WIDTH, args.ascii))
-                    .set("free", units.fmt(stats.available()))
-                    .set("free-percents", format!("{:>3.0}%", 100.0 * free_share));
-            }
-            
-            // Always set the dedicated inode columns regardless of mode
-            if let Some(inodes) = &stats.inodes {
-                let iuse_share = inodes.use_share();
-                sub
-                    .set("inodes", inodes.files)
-                    .set("iused", inodes.used())
-                    .set("iuse-percents", format!("{:>3.0}%", 100.0 * iuse_share))
-                    .set_md("ibar", progress_bar_md(iuse_share, INODES_BAR_WIDTH, args.ascii))
-                    .set("ifree", inodes.favail);
+            if inodes_mode && stats.inodes.is_none() {
+                sub.set("use-error", "no inodes data");
             }
         } else if mount.is_unreachable() {
             sub.set("use-error", "unreachable");
         }
     }
-    let mut skin = if color {
-        make_colored_skin()
-    } else {
-        MadSkin::no_style()
-    };
-    if args.ascii {
-        skin.limit_to_ascii();
+    if args.total {
+        let totals = Totals::compute(mounts);
+        expander.sub("rows"); // empty row before the totals
+        let sub = expander.sub("rows");
+        let label_col = Totals::label_col(args.cols.cols());
+        for &col in args.cols.cols() {
+            if Some(col) == label_col {
+                sub.set(col.name(), "total");
+            } else {
+                set_cell(sub, col, &totals.value(col, inodes_mode), args);
+            }
+        }
     }
+    let skin = make_skin(color, args.ascii);
 
     let mut tbl = TableBuilder::default();
     for col in args.cols.cols() {
         tbl.col(
             minimad::Col::new(
                 col.title(inodes_mode),
+                cell_template(*col),
+            )
+            .align_content(col.content_align())
+            .align_header(col.header_align())
+        );
+    }
+
+    skin.print_owning_expander_md(&expander, &tbl);
+}
+
+/// Print the jobs view
+pub fn print_jobs(jobs: &[&JobStat], color: bool, args: &Args) {
+    let units = args.units;
+    let mut expander = OwningTemplateExpander::new();
+    expander.set_default("");
+    for job in jobs {
+        expander
+            .sub("rows")
+            .set("job-id", &job.job_id)
+            .set("target", &job.target)
+            .set("read", units.fmt(job.read_bytes))
+            .set("write", units.fmt(job.write_bytes))
+            .set("ops", job.metadata_ops);
+    }
+    let skin = make_skin(color, args.ascii);
+    let mut tbl = TableBuilder::default();
+    for &col in ALL_JOB_COLS {
+        tbl.col(
+            minimad::Col::new(
+                col.title(),
                 match col {
-                    Col::Id => "${id}",
-                    Col::Dev => "${dev-major}:${dev-minor}",
-                    Col::Filesystem => "${filesystem}",
-                    Col::Label => "${label}",
-                    Col::Disk => "${disk}",
-                    Col::Type => "${type}",
-                    Col::Remote => "${remote}",
-                    Col::Used => "~~${used}~~",
-                    Col::Use => "~~${use-percents}~~ ${bar}~~${use-error}~~",
-                    Col::UsePercent => "~~${use-percents}~~",
-                    Col::Free => "*${free}*",
-                    Col::FreePercent => "*${free-percents}*",
-                    Col::Size => "**${size}**",
-                    Col::InodesFree => "*${ifree}*",
-                    Col::InodesUsed => "~~${iused}~~",
-                    Col::InodesUse => "~~${iuse-percents}~~ ${ibar}",
-                    Col::InodesUsePercent => "~~${iuse-percents}~~",
-                    Col::InodesCount => "**${inodes}**",
-                    Col::MountPoint => "${mount-point}",
-                    Col::FsName => "${fs-name}",
-                    Col::Uuid => "${uuid}",
-                    Col::PartUuid => "${part_uuid}",
-                    Col::StripeCount => "${stripe-count}",
-                    Col::StripeSize => "${stripe-size}",
-                    Col::LustreVersion => "${lustre-version}",
-                    Col::PoolName => "${pool-name}",
-                    Col::ComponentType => "${component-type}",
-                    Col::ComponentIndex => "${component-index}",
-                    Col::MirrorCount => "${mirror-count}",
+                    JobCol::JobId => "${job-id}",
+                    JobCol::Target => "${target}",
+                    JobCol::ReadBytes => "*${read}*",
+                    JobCol::WriteBytes => "~~${write}~~",
+                    JobCol::MetadataOps => "**${ops}**",
                 }
             )
             .align_content(col.content_align())
-            .align_header(col.header_align())
+            .align_header(Alignment::Center)
         );
     }
+    skin.print_owning_expander_md(&expander, &tbl);
+}
 
+/// Print the MDT and OST served by this node
+pub fn print_server_targets(targets: &[ServerTarget], color: bool, args: &Args) {
+    let units = args.units;
+    let mut expander = OwningTemplateExpander::new();
+    expander.set_default("");
+    for target in targets {
+        let sub = expander
+            .sub("rows")
+            .set("target", &target.name)
+            .set("kind", target.name.kind)
+            .set_option("device", target.device.as_ref())
+            .set_option("backend", target.backend);
+        if let Some(recovery) = &target.recovery {
+            sub.set("recovery", &recovery.status);
+            if let (Some(completed), Some(total)) = (recovery.completed_clients, recovery.total_clients) {
+                sub.set("clients", format!("{completed}/{total}"));
+            }
+        }
+        if let Some(exports) = target.exports {
+            sub.set("exports", exports);
+        }
+        if target.read_only == Some(true) {
+            sub.set("ro", "x");
+        }
+        if let Some(size) = target.size {
+            sub.set("size", units.fmt(size));
+        }
+        if let Some(available) = target.available {
+            sub.set("free", units.fmt(available));
+        }
+        if let Some(mount_point) = &target.mount_point {
+            sub.set("mount-point", mount_point.to_string_lossy());
+        }
+    }
+    let skin = make_skin(color, args.ascii);
+    let mut tbl = TableBuilder::default();
+    for (title, template, align) in [
+        ("target", "${target}", Alignment::Left),
+        ("kind", "${kind}", Alignment::Center),
+        ("device", "${device}", Alignment::Left),
+        ("fs type", "${backend}", Alignment::Center),
+        ("recovery", "${recovery}", Alignment::Center),
+        ("clients", "${clients}", Alignment::Center),
+        ("exports", "${exports}", Alignment::Right),
+        ("ro", "${ro}", Alignment::Center),
+        ("size", "**${size}**", Alignment::Right),
+        ("free", "*${free}*", Alignment::Right),
+        ("mount point", "${mount-point}", Alignment::Left),
+    ] {
+        tbl.col(
+            minimad::Col::new(title, template)
+                .align_content(align)
+                .align_header(Alignment::Center)
+        );
+    }
     skin.print_owning_expander_md(&expander, &tbl);
 }
 
+/// Print the changelog users of the local MDTs
+pub fn print_changelog_users(users: &[ChangelogUser], color: bool, args: &Args) {
+    let mut expander = OwningTemplateExpander::new();
+    expander.set_default("");
+    for user in users {
+        let sub = expander
+            .sub("rows")
+            .set("mdt", &user.mdt)
+            .set("id", &user.id)
+            .set("last-cleared", user.last_cleared)
+            .set("current-index", user.current_index)
+            .set("lag", user.lag());
+        if let Some(idle) = user.idle {
+            sub.set("idle", changelog::format_duration(idle));
+        }
+    }
+    let skin = make_skin(color, args.ascii);
+    let mut tbl = TableBuilder::default();
+    for (title, template, align) in [
+        ("MDT", "${mdt}", Alignment::Left),
+        ("user", "${id}", Alignment::Left),
+        ("last cleared", "${last-cleared}", Alignment::Right),
+        ("current index", "${current-index}", Alignment::Right),
+        ("lag", "~~${lag}~~", Alignment::Right),
+        ("idle", "${idle}", Alignment::Right),
+    ] {
+        tbl.col(
+            minimad::Col::new(title, template)
+                .align_content(align)
+                .align_header(Alignment::Center)
+        );
+    }
+    skin.print_owning_expander_md(&expander, &tbl);
+}
+
+fn make_skin(color: bool, ascii: bool) -> MadSkin {
+    let mut skin = if color {
+        make_colored_skin()
+    } else {
+        MadSkin::no_style()
+    };
+    if ascii {
+        skin.limit_to_ascii();
+    }
+    skin
+}
+
 fn make_colored_skin() -> MadSkin {
     MadSkin {
         bold: CompoundStyle::with_fg(AnsiValue(SIZE_COLOR)), // size
@@ -0,0 +1,98 @@
+use {
+    crate::{
+        jobstats::JobStat,
+        mount_row::MountRow,
+    },
+    lfs_core::*,
+    std::path::PathBuf,
+};
+
+/// A builder of the mounts of the tests.
+///
+/// By default, the mount is an ext4 filesystem on /dev/sda1,
+/// whose stats are excluded.
+pub struct TestMount {
+    mount: Mount,
+}
+
+/// A mount with the given id and mount point
+pub fn mount(id: MountId, mount_point: &str) -> TestMount {
+    TestMount {
+        mount: Mount {
+            info: MountInfo {
+                id,
+                parent: 1,
+                dev: DeviceId { major: 8, minor: 1 },
+                root: PathBuf::from("/"),
+                mount_point: PathBuf::from(mount_point),
+                fs: "/dev/sda1".to_string(),
+                fs_type: "ext4".to_string(),
+                bound: false,
+            },
+            fs_label: None,
+            disk: None,
+            stats: Err(StatsError::Excluded),
+            uuid: None,
+            part_uuid: None,
+        },
+    }
+}
+
+impl TestMount {
+    pub fn dev(mut self, major: u32, minor: u32) -> Self {
+        self.mount.info.dev = DeviceId { major, minor };
+        self
+    }
+    pub fn fs(mut self, fs: &str) -> Self {
+        self.mount.info.fs = fs.to_string();
+        self
+    }
+    pub fn fs_type(mut self, fs_type: &str) -> Self {
+        self.mount.info.fs_type = fs_type.to_string();
+        self
+    }
+    pub fn uuid(mut self, uuid: &str) -> Self {
+        self.mount.uuid = Some(uuid.to_string());
+        self
+    }
+    pub fn disk(mut self, disk: Disk) -> Self {
+        self.mount.disk = Some(disk);
+        self
+    }
+    /// Stats without inodes
+    pub fn stats(mut self, bsize: u64, blocks: u64, bfree: u64, bavail: u64) -> Self {
+        self.mount.stats = Ok(Stats {
+            bsize,
+            blocks,
+            bfree,
+            bavail,
+            inodes: None,
+        });
+        self
+    }
+    /// Add inodes to the stats set with `stats`
+    pub fn inodes(mut self, files: u64, ffree: u64, favail: u64) -> Self {
+        if let Ok(stats) = &mut self.mount.stats {
+            stats.inodes = Some(Inodes { files, ffree, favail });
+        }
+        self
+    }
+    pub fn stats_error(mut self, error: StatsError) -> Self {
+        self.mount.stats = Err(error);
+        self
+    }
+    pub fn row(self) -> MountRow {
+        MountRow::new(self.mount)
+    }
+}
+
+/// A job without activity
+pub fn job(job_id: &str, target: &str) -> JobStat {
+    JobStat {
+        job_id: job_id.to_string(),
+        target: target.to_string(),
+        read_bytes: 0,
+        write_bytes: 0,
+        metadata_ops: 0,
+    }
+}
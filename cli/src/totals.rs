@@ -0,0 +1,283 @@
+use {
+    crate::{
+        col::Col,
+        col_value::ColValue,
+        lustre_target::{LustreTargetName, TargetKind},
+        mount_row::MountRow,
+    },
+    std::collections::HashSet,
+};
+
+/// Sums of the inodes of the displayed filesystems
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InodesTotals {
+    pub files: u64,
+    pub used: u64,
+    /// free inodes available to non-root users
+    pub favail: u64,
+}
+
+impl InodesTotals {
+    pub fn use_share(self) -> f64 {
+        if self.files == 0 {
+            0.0
+        } else {
+            self.used as f64 / self.files as f64
+        }
+    }
+}
+
+/// Sums of the stats of the displayed filesystems, for the `--total` row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Totals {
+    /// number of filesystems counted, the targets of a
+    /// Lustre filesystem counting as one
+    pub count: usize,
+    pub size: u64,
+    pub used: u64,
+    pub available: u64,
+    /// None when no counted filesystem has inodes stats
+    pub inodes: Option<InodesTotals>,
+}
+
+impl Totals {
+    /// Sum the stats of the given mounts, which are expected
+    /// to be already filtered and deduplicated.
+    ///
+    /// A filesystem is counted only once even when several rows show it.
+    /// The Lustre client row (`filesystem_summary`) is the sum of its
+    /// targets, so it's skipped when targets of its filesystem are
+    /// displayed too.
+    ///
+    /// Like in `lfs df`, the space of a Lustre filesystem is the one of
+    /// its OSTs and its inodes are the ones of its MDTs.
+    pub fn compute(mounts: &[&MountRow]) -> Self {
+        let fsnames_of_targets: HashSet<String> = mounts
+            .iter()
+            .filter(|m| m.info.fs_type == "lustre")
+            .filter_map(|m| m.info.fs.parse::<LustreTargetName>().ok())
+            .map(|target| target.fsname)
+            .collect();
+        let mut counted = HashSet::new();
+        let mut counted_lustre_fs = HashSet::new();
+        let mut totals = Self {
+            count: 0,
+            size: 0,
+            used: 0,
+            available: 0,
+            inodes: None,
+        };
+        for mount in mounts {
+            let is_lustre = mount.info.fs_type == "lustre";
+            let is_summary = mount.info.fs == "filesystem_summary";
+            let fsname = mount.lustre.as_ref().and_then(|info| info.fsname.as_ref());
+            if is_summary && fsname.is_some_and(|fsname| fsnames_of_targets.contains(fsname)) {
+                continue;
+            }
+            // Lustre rows share the device of their client mount and are
+            // repeated for each mount point of the filesystem, so they're
+            // keyed by filesystem name for the client and by target name
+            let key = match fsname {
+                Some(fsname) if is_lustre && is_summary => format!("lustre:{fsname}"),
+                _ if is_lustre && !is_summary => format!("lustre:{}", mount.info.fs),
+                _ => format!("{}:{}", mount.info.dev.major, mount.info.dev.minor),
+            };
+            if !counted.insert(key) {
+                continue;
+            }
+            let Some(stats) = mount.stats() else {
+                continue;
+            };
+            let target = Some(*mount)
+                .filter(|m| m.info.fs_type == "lustre")
+                .and_then(|m| m.info.fs.parse::<LustreTargetName>().ok());
+            let (sums_space, sums_inodes) = match &target {
+                Some(target) => {
+                    // the targets of a filesystem make only one filesystem
+                    if counted_lustre_fs.insert(target.fsname.clone()) {
+                        totals.count += 1;
+                    }
+                    (target.kind == TargetKind::Ost, target.kind == TargetKind::Mdt)
+                }
+                None => {
+                    totals.count += 1;
+                    (true, true)
+                }
+            };
+            if sums_space {
+                totals.size += stats.size();
+                totals.used += stats.used();
+                totals.available += stats.available();
+            }
+            if let Some(inodes) = stats.inodes.as_ref().filter(|_| sums_inodes) {
+                let sum = totals.inodes.get_or_insert(InodesTotals {
+                    files: 0,
+                    used: 0,
+                    favail: 0,
+                });
+                sum.files += inodes.files;
+                sum.used += inodes.used();
+                sum.favail += inodes.favail;
+            }
+        }
+        totals
+    }
+    /// Share of the space used, computed like the one of a filesystem
+    /// (the space reserved to root isn't counted)
+    pub fn use_share(&self) -> f64 {
+        let counted = self.used + self.available;
+        if counted == 0 {
+            0.0
+        } else {
+            self.used as f64 / counted as f64
+        }
+    }
+    /// The value of a column in the totals row, which is missing
+    /// for the columns which can't be summed
+    pub fn value(&self, col: Col, inodes_mode: bool) -> ColValue {
+        let inodes = self.inodes;
+        match col {
+            Col::Used if inodes_mode => ColValue::int(inodes.map(|i| i.used)),
+            Col::Use | Col::UsePercent if inodes_mode => ColValue::float(inodes.map(|i| i.use_share())),
+            Col::Free if inodes_mode => ColValue::int(inodes.map(|i| i.favail)),
+            Col::FreePercent if inodes_mode => ColValue::float(inodes.map(|i| 1.0 - i.use_share())),
+            Col::Size if inodes_mode => ColValue::int(inodes.map(|i| i.files)),
+            Col::Used => ColValue::Size(self.used),
+            Col::Use | Col::UsePercent => ColValue::Float(self.use_share()),
+            Col::Free => ColValue::Size(self.available),
+            Col::FreePercent => ColValue::Float(1.0 - self.use_share()),
+            Col::Size => ColValue::Size(self.size),
+            Col::InodesUsed => ColValue::int(inodes.map(|i| i.used)),
+            Col::InodesUse | Col::InodesUsePercent => ColValue::float(inodes.map(|i| i.use_share())),
+            Col::InodesFree => ColValue::int(inodes.map(|i| i.favail)),
+            Col::InodesCount => ColValue::int(inodes.map(|i| i.files)),
+            _ => ColValue::Missing,
+        }
+    }
+    /// The column of the totals row holding the "total" label: the
+    /// first one of the displayed columns able to hold it
+    pub fn label_col(cols: &[Col]) -> Option<Col> {
+        cols.iter()
+            .copied()
+            .find(|col| matches!(col, Col::Filesystem | Col::MountPoint | Col::FsName))
+    }
+}
+
+#[cfg(test)]
+mod totals_computation {
+    use {
+        super::*,
+        crate::test_util,
+        lfs_core::MountId,
+    };
+
+    fn mount(id: MountId, fs: &str, fs_type: &str, minor: u32, blocks: u64, bfree: u64, bavail: u64) -> MountRow {
+        test_util::mount(id, &format!("/mnt/{id}"))
+            .dev(8, minor)
+            .fs(fs)
+            .fs_type(fs_type)
+            .stats(1000, blocks, bfree, bavail)
+            .inodes(100, 40, 40)
+            .row()
+    }
+
+    /// The client row of a Lustre filesystem
+    fn client(id: MountId, fsname: &str, blocks: u64, bfree: u64, bavail: u64) -> MountRow {
+        let mut row = mount(id, "filesystem_summary", "lustre", 9, blocks, bfree, bavail);
+        let mut info = crate::LustreInfo::new();
+        info.fsname = Some(fsname.to_string());
+        row.lustre = Some(info);
+        row
+    }
+
+    #[test]
+    fn sums_displayed_filesystems() {
+        let a = mount(10, "/dev/sda1", "ext4", 1, 100, 60, 50);
+        let b = mount(11, "/dev/sdb1", "xfs", 2, 300, 100, 100);
+        let totals = Totals::compute(&[&a, &b]);
+        assert_eq!(totals.count, 2);
+        assert_eq!(totals.size, 400_000);
+        assert_eq!(totals.used, 240_000);
+        assert_eq!(totals.available, 150_000);
+        assert_eq!(
+            totals.inodes,
+            Some(InodesTotals {
+                files: 200,
+                used: 120,
+                favail: 80,
+            }),
+        );
+        assert!((totals.use_share() - 240.0 / 390.0).abs() < 1e-9);
+        assert_eq!(totals.value(Col::Free, false), ColValue::Size(150_000));
+        assert_eq!(totals.value(Col::Free, true), ColValue::Int(80));
+        assert_eq!(totals.value(Col::Label, false), ColValue::Missing);
+    }
+
+    #[test]
+    fn bind_mounts_are_counted_once() {
+        let a = mount(10, "/dev/sda1", "ext4", 1, 100, 60, 50);
+        let bind = mount(12, "/dev/sda1", "ext4", 1, 100, 60, 50);
+        let totals = Totals::compute(&[&a, &bind]);
+        assert_eq!(totals.count, 1);
+        assert_eq!(totals.size, 100_000);
+    }
+
+    #[test]
+    fn lustre_client_isnt_added_to_its_targets() {
+        let mdt = mount(20, "lustre-MDT0000_UUID", "lustre", 9, 10, 5, 5);
+        let ost0 = mount(21, "lustre-OST0000_UUID", "lustre", 9, 100, 50, 50);
+        let ost1 = mount(22, "lustre-OST0001_UUID", "lustre", 9, 100, 20, 20);
+        let client = client(23, "lustre", 200, 70, 70);
+        let totals = Totals::compute(&[&mdt, &ost0, &ost1, &client]);
+        assert_eq!(totals.count, 1);
+        assert_eq!(totals.size, 200_000);
+        assert_eq!(totals.used, 130_000);
+        // the inodes are the ones of the MDT
+        assert_eq!(totals.inodes.map(|i| i.files), Some(100));
+        let totals = Totals::compute(&[&ost0, &ost1]);
+        assert_eq!(totals.size, 200_000);
+        assert_eq!(totals.inodes, None);
+        let totals = Totals::compute(&[&client]);
+        assert_eq!(totals.count, 1);
+        assert_eq!(totals.size, 200_000);
+    }
+
+    #[test]
+    fn lustre_filesystem_mounted_twice_is_counted_once() {
+        let ost = mount(21, "lustre-OST0000_UUID", "lustre", 9, 100, 50, 50);
+        let ost_again = mount(31, "lustre-OST0000_UUID", "lustre", 9, 100, 50, 50);
+        let client_a = client(23, "lustre", 100, 50, 50);
+        let client_b = client(33, "lustre", 100, 50, 50);
+        assert_ne!(client_a.info.mount_point, client_b.info.mount_point);
+        let totals = Totals::compute(&[&client_a, &client_b]);
+        assert_eq!(totals.count, 1);
+        assert_eq!(totals.size, 100_000);
+        let totals = Totals::compute(&[&ost, &ost_again, &client_a, &client_b]);
+        assert_eq!(totals.count, 1);
+        assert_eq!(totals.size, 100_000);
+        assert_eq!(totals.used, 50_000);
+    }
+
+    #[test]
+    fn lustre_client_of_another_filesystem_is_added() {
+        let ost = mount(21, "lustre-OST0000_UUID", "lustre", 9, 100, 50, 50);
+        let lustre_client = client(23, "lustre", 100, 50, 50);
+        let scratch_client = client(24, "scratch", 300, 100, 100);
+        let totals = Totals::compute(&[&ost, &lustre_client, &scratch_client]);
+        assert_eq!(totals.count, 2);
+        assert_eq!(totals.size, 400_000);
+        assert_eq!(totals.used, 250_000);
+    }
+
+    #[test]
+    fn use_share_of_no_space() {
+        let totals = Totals {
+            count: 1,
+            size: 100,
+            used: 0,
+            available: 0,
+            inodes: None,
+        };
+        assert_eq!(totals.use_share(), 0.0);
+    }
+}
@@ -0,0 +1,90 @@
+use {
+    crate::{
+        args::Args,
+        format::Format,
+    },
+    serde::Deserialize,
+    std::collections::BTreeMap,
+};
+
+/// The views coming with dysk, with the same syntax as the
+/// `[views]` section of the configuration
+static BUILTIN_VIEWS: &str = r#"
+[full-disks]
+cols = "fs+type+used+use+free+size+mount"
+filter = "use > 90%"
+sort = "use-desc"
+
+[inodes]
+cols = "fs+type+inodes+mount"
+sort = "inodes-desc"
+
+[lustre-ost]
+cols = "fs+target+used+use+free+size+inodes"
+filter = "component_type = OST"
+sort = "target"
+
+[remote]
+cols = "fs+type+used+use+free+size+mount"
+filter = "remote = yes"
+"#;
+
+/// A named view, chosen with `--view name` or `@name`, bundling
+/// columns, filter, sort, and output format
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct View {
+    pub cols: Option<String>,
+    pub sort: Option<String>,
+    pub filter: Option<String>,
+    pub format: Option<Format>,
+}
+
+impl View {
+    /// The built-in views, by name
+    pub fn builtins() -> BTreeMap<String, View> {
+        toml::from_str(BUILTIN_VIEWS).expect("invalid built-in views")
+    }
+    /// Apply the view to the arguments which weren't given
+    /// on the command line
+    pub fn apply<F>(&self, args: &mut Args, given: F)
+    where
+        F: Fn(&str) -> bool,
+    {
+        if let Some(cols) = self.cols.as_ref().filter(|_| !given("cols")) {
+            args.cols_def = Some(cols.clone());
+        }
+        if let Some(sort) = self.sort.as_ref().filter(|_| !given("sort")) {
+            args.sort = Some(sort.clone());
+        }
+        if let Some(filter) = self.filter.as_ref().filter(|_| !given("filter")) {
+            args.filter = Some(filter.clone());
+        }
+        if let Some(format) = self.format.filter(|_| !given("format") && !given("json") && !given("csv")) {
+            args.format = Some(format);
+        }
+    }
+}
+
+#[test]
+fn test_builtin_views() {
+    use crate::{
+        col::Col,
+        cols::Cols,
+        filter::Filter,
+        sorting::Sorting,
+    };
+    let views = View::builtins();
+    for name in ["full-disks", "inodes", "lustre-ost", "remote"] {
+        let view = &views[name];
+        if let Some(cols) = &view.cols {
+            assert!(cols.parse::<Cols>().is_ok(), "invalid cols in view {name}");
+        }
+        if let Some(sort) = &view.sort {
+            assert!(sort.parse::<Sorting<Col>>().is_ok(), "invalid sort in view {name}");
+        }
+        if let Some(filter) = &view.filter {
+            assert!(filter.parse::<Filter<Col>>().is_ok(), "invalid filter in view {name}");
+        }
+    }
+}